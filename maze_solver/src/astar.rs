@@ -1,61 +1,105 @@
 use std::{cmp::Reverse, collections::BinaryHeap};
 
-use crate::maze::*;
+use crate::{heuristic::*, maze::*, solver::*};
 
 const STEPS_PER_UPDATE: usize = 3;
 
 /// Element type for the open list priority queue
 #[derive(Clone, Copy)]
-struct SearchNode {
+pub(crate) struct SearchNode {
     /// Cost from starting node
-    g: f32,
+    pub(crate) g: f32,
+
+    /// Heuristic estimate to the goal, breaks ties between equal f values
+    pub(crate) h: f32,
 
     /// f: g + heuristic
-    f: f32,
+    pub(crate) f: f32,
 
-    position: GridPosition,
+    pub(crate) position: GridPosition,
 }
 
 impl SearchNode {
-    fn new(g: f32, f: f32, position: GridPosition) -> Self {
-        Self { g, f, position }
+    pub(crate) fn new(g: f32, h: f32, f: f32, position: GridPosition) -> Self {
+        Self { g, h, f, position }
     }
 }
 
 impl Eq for SearchNode {}
 
 impl Ord for SearchNode {
+    /// Order by f, preferring nodes closer to the goal on ties
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.f.partial_cmp(&other.f).unwrap()
+        self.f.total_cmp(&other.f).then(self.h.total_cmp(&other.h))
     }
 }
 
 impl PartialOrd for SearchNode {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.f.partial_cmp(&other.f)
+        Some(self.cmp(other))
     }
 }
 
 impl PartialEq for SearchNode {
     fn eq(&self, other: &Self) -> bool {
-        self.f == other.f
+        self.cmp(other) == std::cmp::Ordering::Equal
     }
 }
 
+/// Cells of an open list still waiting to be expanded, skipping superseded entries
+pub(crate) fn open_positions(
+    open_list: &BinaryHeap<Reverse<SearchNode>>,
+    search_grid: &[Vec<f32>],
+    closed_grid: &[Vec<bool>],
+) -> Vec<GridPosition> {
+    open_nodes(open_list, search_grid, closed_grid)
+        .map(|node| node.position)
+        .collect()
+}
+
+/// Number of cells `open_positions` returns
+pub(crate) fn open_count(
+    open_list: &BinaryHeap<Reverse<SearchNode>>,
+    search_grid: &[Vec<f32>],
+    closed_grid: &[Vec<bool>],
+) -> usize {
+    open_nodes(open_list, search_grid, closed_grid).count()
+}
+
+fn open_nodes<'a>(
+    open_list: &'a BinaryHeap<Reverse<SearchNode>>,
+    search_grid: &'a [Vec<f32>],
+    closed_grid: &'a [Vec<bool>],
+) -> impl Iterator<Item = SearchNode> + 'a {
+    open_list.iter().map(|node| node.0).filter(|node| {
+        let (x, y) = (node.position.x as usize, node.position.y as usize);
+        !closed_grid[x][y] && node.g == search_grid[x][y]
+    })
+}
+
 pub struct MazeSolverAStar {
     maze: Maze,
     position_start: GridPosition,
     position_end: GridPosition,
+    /// Reaching any of these ends the search, `position_end` becomes the goal that was reached
+    goals: Vec<GridPosition>,
 
-    /// Stores f(n) values
+    heuristic: Heuristic,
+    /// Heuristic weight ε, f = g + ε * h finds a path costing at most ε times the cheapest one
+    weight: f32,
+
+    /// Best known g(n) values
     search_grid: Vec<Vec<f32>>,
 
+    /// True once a cell has been expanded with its best known g(n)
+    closed_grid: Vec<Vec<bool>>,
+
     /// Open list of nodes to visit
     current_search_positions: BinaryHeap<Reverse<SearchNode>>,
 
     path_edges: MazePathEdges,
 
-    complete: bool,
+    outcome: SearchOutcome,
     complete_path: Vec<GridPosition>,
 
     expanded_nodes: Vec<GridPosition>,
@@ -63,10 +107,16 @@ pub struct MazeSolverAStar {
 
 impl MazeSolverAStar {
     pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
-        let search_grid = vec![vec![f32::MAX; maze.grid[0].len()]; maze.grid.len()];
+        let mut search_grid = vec![vec![f32::MAX; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[position_start.x as usize][position_start.y as usize] = 0.0;
+
+        let closed_grid = vec![vec![false; maze.grid[0].len()]; maze.grid.len()];
+
+        let heuristic = Heuristic::default_for(maze.connectivity);
+        let h = heuristic.distance(&position_start, &position_end);
 
         let mut current_search_positions = BinaryHeap::new();
-        current_search_positions.push(Reverse(SearchNode::new(0.0, 0.0, position_start)));
+        current_search_positions.push(Reverse(SearchNode::new(0.0, h, h, position_start)));
 
         let path_edges = MazePathEdges::new_with_maze(&maze);
 
@@ -74,95 +124,157 @@ impl MazeSolverAStar {
             maze,
             position_start,
             position_end,
+            goals: vec![position_end],
+            heuristic,
+            weight: 1.0,
             search_grid,
+            closed_grid,
 
             current_search_positions,
             path_edges,
 
-            complete: false,
+            outcome: SearchOutcome::InProgress,
             complete_path: Vec::new(),
 
             expanded_nodes: Vec::new(),
         }
     }
 
-    /// 1 step of iterative A* search, expanding the current deepest node by 1
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        if !heuristic.is_admissible(self.maze.connectivity) {
+            log::warn!(
+                "{:?} heuristic is not admissible with {:?} movement, A* may not find the cheapest path",
+                heuristic,
+                self.maze.connectivity
+            );
+        }
+        self.heuristic = heuristic;
+        self.reset_open_list();
+        self
+    }
+
+    /// Trade optimality for fewer expansions, weights below 1 are clamped to 1
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.max(1.0);
+        self.reset_open_list();
+        self
+    }
+
+    /// Search for the nearest of several goals instead, the first one is reported as the end until a goal is reached.
+    /// The heuristic is the distance to the closest goal. Panics if `goals` is empty.
+    pub fn with_goals(mut self, goals: Vec<GridPosition>) -> Self {
+        assert!(!goals.is_empty(), "A* needs at least 1 goal");
+        self.position_end = goals[0];
+        self.goals = goals;
+        self.reset_open_list();
+        self
+    }
+
+    /// Re-seed the open list with the start node after the priority function changed
+    fn reset_open_list(&mut self) {
+        let h = self.h(&self.position_start);
+        self.current_search_positions.clear();
+        self.current_search_positions.push(Reverse(SearchNode::new(
+            0.0,
+            h,
+            self.weight * h,
+            self.position_start,
+        )));
+    }
+
+    /// Proven bound on path cost / cheapest path cost once a path was found.
+    /// Every cheaper path passes through an open node, so the smallest unweighted g + h in the open list bounds the optimum.
+    fn proven_suboptimality(&self) -> f32 {
+        let path_cost = self.maze.path_cost(&self.complete_path);
+        let lower_bound = self
+            .current_search_positions
+            .iter()
+            .map(|node| node.0.g + node.0.h)
+            .fold(path_cost, f32::min);
+
+        if lower_bound > 0.0 {
+            path_cost / lower_bound
+        } else {
+            1.0
+        }
+    }
+
+    /// 1 step of iterative A* search, expanding the open node with the lowest f by 1
     fn search(&mut self) {
-        if self.current_search_positions.is_empty() || self.complete {
+        if self.outcome != SearchOutcome::InProgress {
             return;
         }
 
-        // XXX: Is this true?
-        // We might add a node multiple times to the open list, only compute if the f value is the least
+        let Some(start) = self.current_search_positions.pop().map(|node| node.0) else {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        };
 
-        let start = self.current_search_positions.pop().unwrap().0;
+        // A cell is pushed again whenever a cheaper path to it is found, skip the superseded entries
+        if start.g > self.search_grid[start.position.x as usize][start.position.y as usize]
+            || self.closed_grid[start.position.x as usize][start.position.y as usize]
+        {
+            return;
+        }
+        self.closed_grid[start.position.x as usize][start.position.y as usize] = true;
         self.expanded_nodes.push(start.position);
 
-        for delta in &SEARCH_ORDER {
-            let cell = start.position.add(delta);
-            if self.maze.is_cell_valid(&cell) {
-                let g = start.g + 1.0;
+        // Only an expanded goal is guaranteed to have been reached by the cheapest path
+        if self.goals.contains(&start.position) {
+            self.position_end = start.position;
+            self.outcome = SearchOutcome::Found;
+            self.complete_path = self
+                .path_edges
+                .build_path(&self.position_start, &self.position_end)
+                .unwrap();
+            print_result_path(self);
+            return;
+        }
+
+        for cell in self.maze.neighbours(&start.position) {
+            let g = start.g + self.maze.move_cost(&start.position, &cell);
+
+            if g < self.search_grid[cell.x as usize][cell.y as usize] {
+                self.search_grid[cell.x as usize][cell.y as usize] = g;
+                // Re-open closed cells, which only happens with inconsistent heuristics
+                self.closed_grid[cell.x as usize][cell.y as usize] = false;
+                self.path_edges.add_edge(&start.position, &cell);
+
                 let h = self.h(&cell);
-                let f = g + h;
-
-                if f < self.search_grid[cell.x as usize][cell.y as usize] {
-                    self.path_edges.add_edge(&start.position, &cell);
-                    self.search_grid[cell.x as usize][cell.y as usize] = f;
-                    self.current_search_positions
-                        .push(Reverse(SearchNode::new(g, f, cell)));
-                }
-
-                if cell == self.position_end {
-                    self.complete = true;
-                    self.complete_path = self
-                        .path_edges
-                        .build_path(&self.position_start, &self.position_end)
-                        .unwrap();
-                    self.print_result_path();
-                    break;
-                }
+                self.current_search_positions.push(Reverse(SearchNode::new(
+                    g,
+                    h,
+                    g + self.weight * h,
+                    cell,
+                )));
             }
         }
     }
 
-    /// Heuristic function - distance from end position to position parameter
+    /// Heuristic function - distance from the closest goal to position parameter
     fn h(&self, position: &GridPosition) -> f32 {
-        // Manhattan distance
-        let x = ((position.x - self.position_end.x) as f32).abs();
-        let y = ((position.y - self.position_end.y) as f32).abs();
-        x + y
-    }
-
-    // XXX: Do not duplicate this code
-    fn print_result_path(&self) {
-        log::info!(
-            "Pathfinding result for A* search from ({},{}) to ({},{}) - ",
-            self.position_start.x,
-            self.position_start.y,
-            self.position_end.x,
-            self.position_end.y
-        );
-
-        let mut path_positions = String::from("Complete path: ");
-        for position in &self.complete_path {
-            path_positions += format!(" ({}, {})", position.x, position.y).as_str();
-        }
-        log::info!("{}", path_positions);
-
-        log::info!("Path cost: {}", self.complete_path.len());
-        log::info!("Number of explored nodes: {}", self.expanded_nodes.len());
+        self.goals
+            .iter()
+            .map(|goal| self.heuristic.distance(position, goal))
+            .fold(f32::MAX, f32::min)
     }
 }
 
-impl MazeSolverDrawable for MazeSolverAStar {
-    fn update(&mut self) {
-        if !self.complete {
-            for _ in 0..STEPS_PER_UPDATE {
-                self.search();
-            }
+impl MazeSolver for MazeSolverAStar {
+    fn name(&self) -> &'static str {
+        if self.weight > 1.0 {
+            "Weighted A*"
+        } else {
+            "A*"
         }
     }
 
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
     fn expanded_nodes(&self) -> &Vec<GridPosition> {
         &self.expanded_nodes
     }
@@ -175,11 +287,71 @@ impl MazeSolverDrawable for MazeSolverAStar {
         &self.position_end
     }
 
-    fn is_complete(&self) -> bool {
-        self.complete
+    fn goals(&self) -> &[GridPosition] {
+        &self.goals
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
     }
 
     fn path(&self) -> &Vec<GridPosition> {
         &self.complete_path
     }
+
+    fn suboptimality_bound(&self) -> Option<f32> {
+        (self.weight > 1.0 && self.outcome == SearchOutcome::Found)
+            .then(|| self.weight.min(self.proven_suboptimality()))
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverAStar {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        open_positions(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn frontier_len(&self) -> usize {
+        open_count(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        let g = self.search_grid[position.x as usize][position.y as usize];
+        let h = self.h(position);
+        (g != f32::MAX).then_some(CellValues {
+            g,
+            h,
+            f: g + self.weight * h,
+        })
+    }
+
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        Some(&self.path_edges)
+    }
 }
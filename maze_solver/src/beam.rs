@@ -0,0 +1,198 @@
+use crate::{astar::SearchNode, heuristic::*, maze::*, solver::*};
+
+/// Breadth-first search keeping only the `width` most promising nodes of every layer, ranked by g + h.
+/// Memory is bounded by the width, but the path can be missed when it runs through pruned nodes.
+pub struct MazeSolverBeam {
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+
+    heuristic: Heuristic,
+    width: usize,
+
+    /// True once a cell was generated, cells are never revisited
+    search_grid: Vec<Vec<bool>>,
+
+    /// Nodes of the current layer
+    current_search_positions: Vec<SearchNode>,
+    path_edges: MazePathEdges,
+
+    /// Whether any generated node was dropped from a layer, an empty beam then only proves the goal unreachable if
+    /// no path to it exists at all
+    pruned: bool,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    expanded_nodes: Vec<GridPosition>,
+}
+
+impl MazeSolverBeam {
+    pub fn new(
+        maze: Maze,
+        position_start: GridPosition,
+        position_end: GridPosition,
+        width: usize,
+    ) -> Self {
+        let mut search_grid = vec![vec![false; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[position_start.x as usize][position_start.y as usize] = true;
+
+        let heuristic = Heuristic::default_for(maze.connectivity);
+        let h = heuristic.distance(&position_start, &position_end);
+
+        let path_edges = MazePathEdges::new_with_maze(&maze);
+
+        Self {
+            maze,
+            position_start,
+            position_end,
+            heuristic,
+            width: width.max(1),
+            search_grid,
+
+            current_search_positions: vec![SearchNode::new(0.0, h, h, position_start)],
+            path_edges,
+
+            pruned: false,
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+        }
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// 1 step of beam search, expanding the whole beam by 1 and keeping the best `width` successors
+    fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+        if self.current_search_positions.is_empty() {
+            if self.pruned
+                && self
+                    .maze
+                    .is_reachable(&self.position_start, &self.position_end)
+            {
+                log::info!("Beam of width {} lost every path to the goal", self.width);
+                self.outcome = SearchOutcome::Aborted;
+            } else {
+                self.outcome = SearchOutcome::Unreachable;
+            }
+            print_result_path(self);
+            return;
+        }
+
+        let mut successors = Vec::new();
+        for start in std::mem::take(&mut self.current_search_positions) {
+            self.expanded_nodes.push(start.position);
+
+            // Only reached when starting on the goal, other cells are tested when generated
+            if start.position == self.position_end {
+                self.outcome = SearchOutcome::Found;
+                self.complete_path = vec![start.position];
+                print_result_path(self);
+                return;
+            }
+
+            for cell in self.maze.neighbours(&start.position) {
+                if self.search_grid[cell.x as usize][cell.y as usize] {
+                    continue;
+                }
+                self.search_grid[cell.x as usize][cell.y as usize] = true;
+                self.path_edges.add_edge(&start.position, &cell);
+
+                if cell == self.position_end {
+                    self.outcome = SearchOutcome::Found;
+                    self.complete_path = self
+                        .path_edges
+                        .build_path(&self.position_start, &self.position_end)
+                        .unwrap();
+                    print_result_path(self);
+                    return;
+                }
+
+                let g = start.g + self.maze.move_cost(&start.position, &cell);
+                let h = self.heuristic.distance(&cell, &self.position_end);
+                successors.push(SearchNode::new(g, h, g + h, cell));
+            }
+        }
+
+        successors.sort();
+        if successors.len() > self.width {
+            successors.truncate(self.width);
+            self.pruned = true;
+        }
+        self.current_search_positions = successors;
+    }
+}
+
+impl MazeSolver for MazeSolverBeam {
+    fn name(&self) -> &'static str {
+        "Beam"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverBeam {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.search();
+        }
+    }
+
+    /// The cells kept in the current layer
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.current_search_positions
+            .iter()
+            .map(|node| node.position)
+            .collect()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.current_search_positions.len()
+    }
+
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        Some(&self.path_edges)
+    }
+}
@@ -1,11 +1,13 @@
 use std::collections::VecDeque;
 
-use crate::maze::*;
+use crate::{maze::*, solver::*};
 
 pub struct MazeSolverBFS {
     maze: Maze,
     position_start: GridPosition,
     position_end: GridPosition,
+    /// Reaching any of these ends the search, `position_end` becomes the goal that was reached
+    goals: Vec<GridPosition>,
 
     /// 0 - new block, 1 - to be visited, 2 - visited
     search_grid: Vec<Vec<u8>>,
@@ -13,7 +15,7 @@ pub struct MazeSolverBFS {
     current_search_positions: VecDeque<GridPosition>,
     path_edges: MazePathEdges,
 
-    complete: bool,
+    outcome: SearchOutcome,
     complete_path: Vec<GridPosition>,
 
     expanded_nodes: Vec<GridPosition>,
@@ -32,20 +34,39 @@ impl MazeSolverBFS {
             maze,
             position_start,
             position_end,
+            goals: vec![position_end],
             search_grid,
 
             current_search_positions,
             path_edges,
 
-            complete: false,
+            outcome: SearchOutcome::InProgress,
             complete_path: Vec::new(),
 
             expanded_nodes: Vec::new(),
         }
     }
 
+    /// Search for the nearest of several goals instead, the first one is reported as the end until a goal is reached.
+    /// Panics if `goals` is empty.
+    pub fn with_goals(mut self, goals: Vec<GridPosition>) -> Self {
+        assert!(!goals.is_empty(), "BFS needs at least 1 goal");
+        self.position_end = goals[0];
+        self.goals = goals;
+        self
+    }
+
     /// 1 step of BFS search, expanding all hinge nodes by 1
     fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+        if self.current_search_positions.is_empty() {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        }
+
         // Number of expansions in current search/update step
         let num_expansions = self.current_search_positions.len();
 
@@ -53,60 +74,50 @@ impl MazeSolverBFS {
             let start = self.current_search_positions.pop_front().unwrap();
             self.expanded_nodes.push(start);
 
-            for delta in &SEARCH_ORDER {
-                let cell = start.add(delta);
-                if self.maze.is_cell_valid(&cell)
-                    && self.search_grid[cell.x as usize][cell.y as usize] == 0
-                {
+            // Only reached when starting on the goal, other cells are tested when generated
+            if self.goals.contains(&start) {
+                self.position_end = start;
+                self.outcome = SearchOutcome::Found;
+                self.complete_path = vec![start];
+                print_result_path(self);
+                break;
+            }
+
+            for cell in self.maze.neighbours(&start) {
+                if self.search_grid[cell.x as usize][cell.y as usize] == 0 {
                     // Mark as "to be visited"
                     self.search_grid[cell.x as usize][cell.y as usize] = 1;
                     self.current_search_positions.push_back(cell);
                     self.path_edges.add_edge(&start, &cell);
 
-                    if cell == self.position_end {
-                        self.complete = true;
+                    if self.goals.contains(&cell) {
+                        self.position_end = cell;
+                        self.outcome = SearchOutcome::Found;
                         self.complete_path = self
                             .path_edges
                             .build_path(&self.position_start, &self.position_end)
                             .unwrap();
-                        self.print_result_path();
+                        print_result_path(self);
                     }
                 }
             }
             self.search_grid[start.x as usize][start.y as usize] = 2;
 
-            if self.complete {
+            if self.outcome == SearchOutcome::Found {
                 break;
             }
         }
     }
+}
 
-    // XXX: Do not duplicate this code
-    fn print_result_path(&self) {
-        log::info!(
-            "Pathfinding result for BFS search from ({},{}) to ({},{}) - ",
-            self.position_start.x,
-            self.position_start.y,
-            self.position_end.x,
-            self.position_end.y
-        );
-
-        let mut path_positions = String::from("Complete path: ");
-        for position in &self.complete_path {
-            path_positions += format!(" ({}, {})", position.x, position.y).as_str();
-        }
-        log::info!("{}", path_positions);
-
-        log::info!("Path cost: {}", self.complete_path.len());
-        log::info!("Number of explored nodes: {}", self.expanded_nodes.len());
+impl MazeSolver for MazeSolverBFS {
+    fn name(&self) -> &'static str {
+        "BFS"
     }
-}
 
-impl MazeSolverDrawable for MazeSolverBFS {
-    fn update(&mut self) {
-        if !self.complete {
-            self.search();
-        }
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
     }
 
     fn expanded_nodes(&self) -> &Vec<GridPosition> {
@@ -121,11 +132,46 @@ impl MazeSolverDrawable for MazeSolverBFS {
         &self.position_end
     }
 
-    fn is_complete(&self) -> bool {
-        self.complete
+    fn goals(&self) -> &[GridPosition] {
+        &self.goals
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
     }
 
     fn path(&self) -> &Vec<GridPosition> {
         &self.complete_path
     }
 }
+
+impl MazeSolverDrawable for MazeSolverBFS {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.search();
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.current_search_positions.iter().copied().collect()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.current_search_positions.len()
+    }
+
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        Some(&self.path_edges)
+    }
+}
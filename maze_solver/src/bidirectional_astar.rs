@@ -0,0 +1,302 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    astar::{open_count, open_positions, SearchNode},
+    heuristic::*,
+    maze::*,
+    solver::*,
+};
+
+const STEPS_PER_UPDATE: usize = 3;
+
+/// One direction of a bidirectional A* search
+struct SearchFrontier {
+    /// Heuristic target, the root of the opposite frontier
+    target: GridPosition,
+
+    /// Best known g(n) values, measured from the root of this frontier
+    search_grid: Vec<Vec<f32>>,
+
+    /// True once a cell has been expanded with its best known g(n)
+    closed_grid: Vec<Vec<bool>>,
+
+    /// Open list of nodes to visit
+    current_search_positions: BinaryHeap<Reverse<SearchNode>>,
+
+    path_edges: MazePathEdges,
+
+    expanded_nodes: Vec<GridPosition>,
+}
+
+impl SearchFrontier {
+    fn frontier(&self) -> Vec<GridPosition> {
+        open_positions(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn frontier_len(&self) -> usize {
+        open_count(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn new(maze: &Maze, root: GridPosition, target: GridPosition, heuristic: Heuristic) -> Self {
+        let mut search_grid = vec![vec![f32::MAX; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[root.x as usize][root.y as usize] = 0.0;
+
+        let h = heuristic.distance(&root, &target);
+        let mut current_search_positions = BinaryHeap::new();
+        current_search_positions.push(Reverse(SearchNode::new(0.0, h, h, root)));
+
+        Self {
+            target,
+            search_grid,
+            closed_grid: vec![vec![false; maze.grid[0].len()]; maze.grid.len()],
+            current_search_positions,
+            path_edges: MazePathEdges::new_with_maze(maze),
+            expanded_nodes: Vec::new(),
+        }
+    }
+
+    fn g(&self, position: &GridPosition) -> f32 {
+        self.search_grid[position.x as usize][position.y as usize]
+    }
+
+    /// Lowest f(n) in the open list, a lower bound on the cost of any path not found yet
+    fn min_f(&self) -> f32 {
+        self.current_search_positions
+            .peek()
+            .map_or(f32::MAX, |node| node.0.f)
+    }
+}
+
+/// A* from both the start and the goal, stopping once neither frontier can improve the best meeting path
+pub struct MazeSolverBidirectionalAStar {
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+
+    heuristic: Heuristic,
+
+    forward: SearchFrontier,
+    backward: SearchFrontier,
+
+    /// Cheapest start -> goal path found so far and the cell where its halves meet
+    best_meeting: Option<(GridPosition, f32)>,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    /// Nodes expanded by both frontiers
+    expanded_nodes: Vec<GridPosition>,
+}
+
+impl MazeSolverBidirectionalAStar {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        let heuristic = Heuristic::default_for(maze.connectivity);
+        let forward = SearchFrontier::new(&maze, position_start, position_end, heuristic);
+        let backward = SearchFrontier::new(&maze, position_end, position_start, heuristic);
+
+        let best_meeting = (position_start == position_end).then_some((position_start, 0.0));
+
+        Self {
+            maze,
+            position_start,
+            position_end,
+            heuristic,
+
+            forward,
+            backward,
+
+            best_meeting,
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+        }
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        if !heuristic.is_admissible(self.maze.connectivity) {
+            log::warn!(
+                "{:?} heuristic is not admissible with {:?} movement, bidirectional A* may not find the cheapest path",
+                heuristic,
+                self.maze.connectivity
+            );
+        }
+        self.heuristic = heuristic;
+        self.forward = SearchFrontier::new(
+            &self.maze,
+            self.position_start,
+            self.position_end,
+            heuristic,
+        );
+        self.backward = SearchFrontier::new(
+            &self.maze,
+            self.position_end,
+            self.position_start,
+            heuristic,
+        );
+        self
+    }
+
+    /// 1 step of search, expanding the best open node of the frontier with the smaller open list
+    fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+
+        // Any path not found yet passes through an open node of both frontiers, so costs at least both minimum f values
+        let best_cost = self.best_meeting.map_or(f32::MAX, |(_, cost)| cost);
+        if self.forward.min_f().max(self.backward.min_f()) >= best_cost {
+            self.finish();
+            return;
+        }
+
+        let expand_forward = self.forward.current_search_positions.len()
+            <= self.backward.current_search_positions.len();
+        let (frontier, other) = if expand_forward {
+            (&mut self.forward, &self.backward)
+        } else {
+            (&mut self.backward, &self.forward)
+        };
+
+        let start = frontier.current_search_positions.pop().unwrap().0;
+
+        // A cell is pushed again whenever a cheaper path to it is found, skip the superseded entries
+        if start.g > frontier.g(&start.position)
+            || frontier.closed_grid[start.position.x as usize][start.position.y as usize]
+        {
+            return;
+        }
+        frontier.closed_grid[start.position.x as usize][start.position.y as usize] = true;
+        frontier.expanded_nodes.push(start.position);
+        self.expanded_nodes.push(start.position);
+
+        for cell in self.maze.neighbours(&start.position) {
+            // Moves cost the destination cell, so the backward frontier pays for the cell it came from
+            let g = if expand_forward {
+                start.g + self.maze.move_cost(&start.position, &cell)
+            } else {
+                start.g + self.maze.move_cost(&cell, &start.position)
+            };
+
+            if g < frontier.g(&cell) {
+                frontier.search_grid[cell.x as usize][cell.y as usize] = g;
+                // Re-open closed cells, which only happens with inconsistent heuristics
+                frontier.closed_grid[cell.x as usize][cell.y as usize] = false;
+                frontier.path_edges.add_edge(&start.position, &cell);
+
+                let h = self.heuristic.distance(&cell, &frontier.target);
+                frontier
+                    .current_search_positions
+                    .push(Reverse(SearchNode::new(g, h, g + h, cell)));
+
+                let other_g = other.g(&cell);
+                if other_g < f32::MAX
+                    && self.best_meeting.is_none_or(|(_, cost)| g + other_g < cost)
+                {
+                    self.best_meeting = Some((cell, g + other_g));
+                }
+            }
+        }
+    }
+
+    /// Join the two half paths through the best meeting cell, or report the goal unreachable
+    fn finish(&mut self) {
+        let Some((meeting, _)) = self.best_meeting else {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        };
+
+        let mut path = self
+            .forward
+            .path_edges
+            .build_path(&self.position_start, &meeting)
+            .unwrap();
+        let mut backward_path = self
+            .backward
+            .path_edges
+            .build_path(&self.position_end, &meeting)
+            .unwrap();
+        backward_path.reverse();
+        path.extend(backward_path.into_iter().skip(1));
+
+        self.outcome = SearchOutcome::Found;
+        self.complete_path = path;
+        print_result_path(self);
+    }
+}
+
+impl MazeSolver for MazeSolverBidirectionalAStar {
+    fn name(&self) -> &'static str {
+        "Bidirectional A*"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    fn backward_expanded_nodes(&self) -> &[GridPosition] {
+        &self.backward.expanded_nodes
+    }
+
+    fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverBidirectionalAStar {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        let mut frontier = self.forward.frontier();
+        frontier.extend(self.backward.frontier());
+        frontier
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.forward.frontier_len() + self.backward.frontier_len()
+    }
+}
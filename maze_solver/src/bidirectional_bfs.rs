@@ -0,0 +1,219 @@
+use crate::{maze::*, solver::*};
+
+const UNVISITED: u32 = u32::MAX;
+
+/// One direction of a bidirectional breadth-first search
+struct SearchFrontier {
+    /// Number of moves from the root of this frontier, UNVISITED if not reached yet
+    search_grid: Vec<Vec<u32>>,
+
+    /// Cells of the deepest layer, expanded together in one step
+    current_search_positions: Vec<GridPosition>,
+    path_edges: MazePathEdges,
+
+    expanded_nodes: Vec<GridPosition>,
+}
+
+impl SearchFrontier {
+    fn new(maze: &Maze, root: GridPosition) -> Self {
+        let mut search_grid = vec![vec![UNVISITED; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[root.x as usize][root.y as usize] = 0;
+
+        Self {
+            search_grid,
+            current_search_positions: vec![root],
+            path_edges: MazePathEdges::new_with_maze(maze),
+            expanded_nodes: Vec::new(),
+        }
+    }
+
+    fn depth(&self, position: &GridPosition) -> u32 {
+        self.search_grid[position.x as usize][position.y as usize]
+    }
+}
+
+/// Breadth-first search from both the start and the goal, meeting in the middle
+pub struct MazeSolverBidirectionalBFS {
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+
+    forward: SearchFrontier,
+    backward: SearchFrontier,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    /// Nodes expanded by both frontiers
+    expanded_nodes: Vec<GridPosition>,
+}
+
+impl MazeSolverBidirectionalBFS {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        let forward = SearchFrontier::new(&maze, position_start);
+        let backward = SearchFrontier::new(&maze, position_end);
+
+        Self {
+            maze,
+            position_start,
+            position_end,
+
+            forward,
+            backward,
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+        }
+    }
+
+    /// 1 step of search, expanding the whole layer of the smaller frontier by 1
+    fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+
+        if self.position_start == self.position_end {
+            self.expanded_nodes.push(self.position_start);
+            self.forward.expanded_nodes.push(self.position_start);
+            self.finish(Some(self.position_start));
+            return;
+        }
+
+        // Either frontier running dry means the start and goal are in different components
+        if self.forward.current_search_positions.is_empty()
+            || self.backward.current_search_positions.is_empty()
+        {
+            self.finish(None);
+            return;
+        }
+
+        let expand_forward = self.forward.current_search_positions.len()
+            <= self.backward.current_search_positions.len();
+        let (frontier, other) = if expand_forward {
+            (&mut self.forward, &self.backward)
+        } else {
+            (&mut self.backward, &self.forward)
+        };
+
+        // Finish the whole layer, the first meeting cell is not necessarily on a shortest path
+        let mut meeting: Option<(GridPosition, u32)> = None;
+        let layer = std::mem::take(&mut frontier.current_search_positions);
+        for start in layer {
+            frontier.expanded_nodes.push(start);
+            self.expanded_nodes.push(start);
+
+            let depth = frontier.depth(&start) + 1;
+            for cell in self.maze.neighbours(&start) {
+                if frontier.depth(&cell) != UNVISITED {
+                    continue;
+                }
+                frontier.search_grid[cell.x as usize][cell.y as usize] = depth;
+                frontier.current_search_positions.push(cell);
+                frontier.path_edges.add_edge(&start, &cell);
+
+                let other_depth = other.depth(&cell);
+                if other_depth != UNVISITED
+                    && meeting.is_none_or(|(_, length)| depth + other_depth < length)
+                {
+                    meeting = Some((cell, depth + other_depth));
+                }
+            }
+        }
+
+        if let Some((cell, _)) = meeting {
+            self.finish(Some(cell));
+        }
+    }
+
+    /// Join the two half paths through the meeting cell, or report the goal unreachable
+    fn finish(&mut self, meeting: Option<GridPosition>) {
+        let Some(meeting) = meeting else {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        };
+
+        let mut path = self
+            .forward
+            .path_edges
+            .build_path(&self.position_start, &meeting)
+            .unwrap();
+        let mut backward_path = self
+            .backward
+            .path_edges
+            .build_path(&self.position_end, &meeting)
+            .unwrap();
+        backward_path.reverse();
+        path.extend(backward_path.into_iter().skip(1));
+
+        self.outcome = SearchOutcome::Found;
+        self.complete_path = path;
+        print_result_path(self);
+    }
+}
+
+impl MazeSolver for MazeSolverBidirectionalBFS {
+    fn name(&self) -> &'static str {
+        "Bidirectional BFS"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    fn backward_expanded_nodes(&self) -> &[GridPosition] {
+        &self.backward.expanded_nodes
+    }
+
+    fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverBidirectionalBFS {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.search();
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        let mut frontier = self.forward.current_search_positions.clone();
+        frontier.extend_from_slice(&self.backward.current_search_positions);
+        frontier
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.forward.current_search_positions.len() + self.backward.current_search_positions.len()
+    }
+}
@@ -0,0 +1,257 @@
+use anyhow::{anyhow, Result};
+use maze_solver::{
+    mapf::DEFAULT_MAX_CBS_NODES, report::ReportFormat, Agent, Connectivity, GeneratorOptions,
+    GoalMode, GridPosition, MapfAlgorithm, MazeGenerateAlgorithm, MazeSolveAlgorithm,
+    SolverOptions,
+};
+
+pub const USAGE: &str = "Usage: maze_solver [--headless] [--algorithms bfs,dfs,dijkstra,astar,bibfs,biastar,iddfs,idastar,greedy,wastar,beam,jps,theta,lazy-theta,lpastar,dstar-lite] [--format json|csv] [--output file] [--max-expansions n] \
+[--compare a,b[,c[,d]]] [--start x,y] [--goal x,y]... [--goal-mode nearest|all] [--connectivity 4|8|8-squeeze|8-always] [--heuristic manhattan|euclidean|octile|chebyshev|zero] [--weight e] [--beam-width n] [maze_file]
+       maze_solver generate [--algorithm backtracker|prim|kruskal|wilson|eller] [--rows n] [--columns n] [--seed n] \
+[--loop-density 0..1] [--output file] [--view]
+       maze_solver mapf [--headless] [--algorithms ca,cbs] [--agent x,y:x,y]... [--agents n] [--seed n] [--max-nodes n] \
+[--connectivity 4|8|8-squeeze|8-always] [--format json|csv] [--output file] maze_file";
+
+pub enum Command {
+    Solve(Args),
+    Generate(GenerateArgs),
+    Mapf(MapfArgs),
+}
+
+impl Command {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        match args.first().map(String::as_str) {
+            Some("generate") => Ok(Self::Generate(GenerateArgs::parse(&args[1..])?)),
+            Some("mapf") => Ok(Self::Mapf(MapfArgs::parse(&args[1..])?)),
+            _ => Ok(Self::Solve(Args::parse(args)?)),
+        }
+    }
+}
+
+pub struct Args {
+    pub maze_file_name: String,
+
+    /// Overrides the start position marked in the maze file
+    pub position_start: Option<GridPosition>,
+    /// Overrides the goal position marked in the maze file, several goals are searched for by `goal_mode`
+    pub goals: Vec<GridPosition>,
+    pub goal_mode: GoalMode,
+
+    pub connectivity: Connectivity,
+    pub options: SolverOptions,
+
+    /// Run solvers to completion without opening the viewer
+    pub headless: bool,
+    pub algorithms: Vec<MazeSolveAlgorithm>,
+    pub format: ReportFormat,
+    /// Report destination, stdout if not set
+    pub output_file_name: Option<String>,
+    /// Abort headless searches after expanding this many nodes
+    pub max_expansions: Option<usize>,
+    /// 2 to 4 algorithms the viewer starts comparing side by side, empty for a single solver
+    pub compare: Vec<MazeSolveAlgorithm>,
+}
+
+impl Args {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut maze_file_name = None;
+        let mut position_start = None;
+        let mut goals = Vec::new();
+        let mut goal_mode = GoalMode::default();
+        let mut connectivity = Connectivity::Four;
+        let mut options = SolverOptions::default();
+        let mut headless = false;
+        let mut algorithms = MazeSolveAlgorithm::ALL.to_vec();
+        let mut format = ReportFormat::Json;
+        let mut output_file_name = None;
+        let mut max_expansions = None;
+        let mut compare = Vec::new();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--start" => position_start = Some(Self::value(&mut args, arg)?.parse()?),
+                "--goal" => goals.push(Self::value(&mut args, arg)?.parse()?),
+                "--goal-mode" => goal_mode = Self::value(&mut args, arg)?.parse()?,
+                "--connectivity" => connectivity = Self::value(&mut args, arg)?.parse()?,
+                "--heuristic" => options.heuristic = Some(Self::value(&mut args, arg)?.parse()?),
+                "--weight" => options.weight = Some(Self::value(&mut args, arg)?.parse()?),
+                "--beam-width" => options.beam_width = Some(Self::value(&mut args, arg)?.parse()?),
+                "--headless" => headless = true,
+                "--algorithms" => {
+                    algorithms = Self::value(&mut args, arg)?
+                        .split(',')
+                        .map(|name| name.trim().parse())
+                        .collect::<Result<Vec<_>>>()?;
+                }
+                "--compare" => {
+                    compare = Self::value(&mut args, arg)?
+                        .split(',')
+                        .map(|name| name.trim().parse())
+                        .collect::<Result<Vec<_>>>()?;
+                }
+                "--format" => format = Self::value(&mut args, arg)?.parse()?,
+                "--max-expansions" => max_expansions = Some(Self::value(&mut args, arg)?.parse()?),
+                "--output" => output_file_name = Some(Self::value(&mut args, arg)?.clone()),
+                _ if arg.starts_with("--") => return Err(anyhow!("Unknown option: {}", arg)),
+                _ => maze_file_name = Some(arg.clone()),
+            }
+        }
+
+        if options.weight.is_some_and(|weight| weight < 1.0) {
+            return Err(anyhow!("Weight must be at least 1"));
+        }
+        if options.beam_width == Some(0) {
+            return Err(anyhow!("Beam width must be at least 1"));
+        }
+        if !compare.is_empty() && !(2..=4).contains(&compare.len()) {
+            return Err(anyhow!("Compare 2 to 4 algorithms"));
+        }
+
+        Ok(Self {
+            maze_file_name: maze_file_name.ok_or_else(|| anyhow!("Missing maze file"))?,
+            position_start,
+            goals,
+            goal_mode,
+            connectivity,
+            options,
+            headless,
+            algorithms,
+            format,
+            output_file_name,
+            max_expansions,
+            compare,
+        })
+    }
+
+    fn value<'a>(args: &mut impl Iterator<Item = &'a String>, option: &str) -> Result<&'a String> {
+        args.next()
+            .ok_or_else(|| anyhow!("Missing value for option {}", option))
+    }
+}
+
+/// Arguments of the `generate` subcommand
+pub struct GenerateArgs {
+    pub algorithm: MazeGenerateAlgorithm,
+    pub options: GeneratorOptions,
+    /// Maze file destination, stdout if not set
+    pub output_file_name: Option<String>,
+    /// Animate generation in the viewer and solve the result
+    pub view: bool,
+}
+
+impl GenerateArgs {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut algorithm = MazeGenerateAlgorithm::RecursiveBacktracker;
+        // Random seed unless one is given, logged so the maze can be reproduced
+        let mut options = GeneratorOptions {
+            seed: rand::random(),
+            ..Default::default()
+        };
+        let mut output_file_name = None;
+        let mut view = false;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--algorithm" => algorithm = Args::value(&mut args, arg)?.parse()?,
+                "--rows" => options.rows = Args::value(&mut args, arg)?.parse()?,
+                "--columns" => options.columns = Args::value(&mut args, arg)?.parse()?,
+                "--seed" => options.seed = Args::value(&mut args, arg)?.parse()?,
+                "--loop-density" => options.loop_density = Args::value(&mut args, arg)?.parse()?,
+                "--output" => output_file_name = Some(Args::value(&mut args, arg)?.clone()),
+                "--view" => view = true,
+                _ => return Err(anyhow!("Unknown generate option: {}", arg)),
+            }
+        }
+
+        if options.rows == 0 || options.columns == 0 {
+            return Err(anyhow!("Maze must have at least 1 row and column"));
+        }
+        if !(0.0..=1.0).contains(&options.loop_density) {
+            return Err(anyhow!("Loop density must be between 0 and 1"));
+        }
+
+        Ok(Self {
+            algorithm,
+            options,
+            output_file_name,
+            view,
+        })
+    }
+}
+
+/// Arguments of the `mapf` subcommand
+pub struct MapfArgs {
+    pub maze_file_name: String,
+
+    /// Agents given on the command line, or else `random_agents` random ones picked with `seed`
+    pub agents: Vec<Agent>,
+    pub random_agents: usize,
+    pub seed: u64,
+
+    pub connectivity: Connectivity,
+    pub algorithms: Vec<MapfAlgorithm>,
+    /// Constraint tree nodes CBS expands before giving up
+    pub max_nodes: usize,
+
+    /// Run solvers to completion without opening the viewer
+    pub headless: bool,
+    pub format: ReportFormat,
+    /// Report destination, stdout if not set
+    pub output_file_name: Option<String>,
+}
+
+impl MapfArgs {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut maze_file_name = None;
+        let mut agents = Vec::new();
+        let mut random_agents = 0;
+        let mut seed = rand::random();
+        let mut connectivity = Connectivity::Four;
+        let mut algorithms = MapfAlgorithm::ALL.to_vec();
+        let mut max_nodes = DEFAULT_MAX_CBS_NODES;
+        let mut headless = false;
+        let mut format = ReportFormat::Json;
+        let mut output_file_name = None;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--agent" => agents.push(Args::value(&mut args, arg)?.parse()?),
+                "--agents" => random_agents = Args::value(&mut args, arg)?.parse()?,
+                "--seed" => seed = Args::value(&mut args, arg)?.parse()?,
+                "--connectivity" => connectivity = Args::value(&mut args, arg)?.parse()?,
+                "--algorithms" => {
+                    algorithms = Args::value(&mut args, arg)?
+                        .split(',')
+                        .map(|name| name.trim().parse())
+                        .collect::<Result<Vec<_>>>()?;
+                }
+                "--max-nodes" => max_nodes = Args::value(&mut args, arg)?.parse()?,
+                "--headless" => headless = true,
+                "--format" => format = Args::value(&mut args, arg)?.parse()?,
+                "--output" => output_file_name = Some(Args::value(&mut args, arg)?.clone()),
+                _ if arg.starts_with("--") => return Err(anyhow!("Unknown mapf option: {}", arg)),
+                _ => maze_file_name = Some(arg.clone()),
+            }
+        }
+
+        if !agents.is_empty() && random_agents > 0 {
+            return Err(anyhow!("Pass either --agent or --agents, not both"));
+        }
+
+        Ok(Self {
+            maze_file_name: maze_file_name.ok_or_else(|| anyhow!("Missing maze file"))?,
+            agents,
+            random_agents,
+            seed,
+            connectivity,
+            algorithms,
+            max_nodes,
+            headless,
+            format,
+            output_file_name,
+        })
+    }
+}
@@ -1,4 +1,4 @@
-use crate::maze::*;
+use crate::{maze::*, solver::*};
 
 const STEPS_PER_UPDATE: usize = 5;
 
@@ -13,7 +13,7 @@ pub struct MazeSolverDFS {
     current_search_positions: Vec<GridPosition>,
     path_edges: MazePathEdges,
 
-    complete: bool,
+    outcome: SearchOutcome,
     complete_path: Vec<GridPosition>,
 
     expanded_nodes: Vec<GridPosition>,
@@ -34,7 +34,7 @@ impl MazeSolverDFS {
             current_search_positions,
             path_edges,
 
-            complete: false,
+            outcome: SearchOutcome::InProgress,
             complete_path: Vec::new(),
 
             expanded_nodes: Vec::new(),
@@ -43,13 +43,12 @@ impl MazeSolverDFS {
 
     /// 1 step of iterative DFS search, expanding the current deepest node by 1
     fn search(&mut self) {
-        if self.complete {
+        if self.outcome != SearchOutcome::InProgress {
             return;
         }
 
         let mut start = None;
-        while !self.current_search_positions.is_empty() {
-            let cell = self.current_search_positions.pop().unwrap();
+        while let Some(cell) = self.current_search_positions.pop() {
             // Since graph is cyclic, nodes in the search stack may already be visited.
             // Only search from unvisited nodes
             if !self.search_grid[cell.x as usize][cell.y as usize] {
@@ -57,63 +56,53 @@ impl MazeSolverDFS {
                 break;
             }
         }
-        if start.is_none() {
+        let Some(start) = start else {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
             return;
-        }
+        };
 
-        let start = start.unwrap();
         self.expanded_nodes.push(start);
 
         self.search_grid[start.x as usize][start.y as usize] = true;
 
-        for delta in SEARCH_ORDER.iter().rev() {
-            let cell = start.add(delta);
-            if self.maze.is_cell_valid(&cell) && !self.search_grid[cell.x as usize][cell.y as usize]
-            {
+        // Only reached when starting on the goal, other cells are tested when generated
+        if start == self.position_end {
+            self.outcome = SearchOutcome::Found;
+            self.complete_path = vec![start];
+            print_result_path(self);
+            return;
+        }
+
+        // Push in reverse so the first neighbour in search order is expanded next
+        let neighbours = self.maze.neighbours(&start).collect::<Vec<_>>();
+        for cell in neighbours.into_iter().rev() {
+            if !self.search_grid[cell.x as usize][cell.y as usize] {
                 self.current_search_positions.push(cell);
                 self.path_edges.add_edge(&start, &cell);
 
                 if cell == self.position_end {
-                    self.complete = true;
+                    self.outcome = SearchOutcome::Found;
                     self.complete_path = self
                         .path_edges
                         .build_path(&self.position_start, &self.position_end)
                         .unwrap();
-                    self.print_result_path();
+                    print_result_path(self);
                     break;
                 }
             }
         }
     }
+}
 
-    // XXX: Do not duplicate this code
-    fn print_result_path(&self) {
-        log::info!(
-            "Pathfinding result for DFS search from ({},{}) to ({},{}) - ",
-            self.position_start.x,
-            self.position_start.y,
-            self.position_end.x,
-            self.position_end.y
-        );
-
-        let mut path_positions = String::from("Complete path: ");
-        for position in &self.complete_path {
-            path_positions += format!(" ({}, {})", position.x, position.y).as_str();
-        }
-        log::info!("{}", path_positions);
-
-        log::info!("Path cost: {}", self.complete_path.len());
-        log::info!("Number of explored nodes: {}", self.expanded_nodes.len());
+impl MazeSolver for MazeSolverDFS {
+    fn name(&self) -> &'static str {
+        "DFS"
     }
-}
 
-impl MazeSolverDrawable for MazeSolverDFS {
-    fn update(&mut self) {
-        if !self.complete {
-            for _ in 0..STEPS_PER_UPDATE {
-                self.search();
-            }
-        }
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
     }
 
     fn expanded_nodes(&self) -> &Vec<GridPosition> {
@@ -128,11 +117,52 @@ impl MazeSolverDrawable for MazeSolverDFS {
         &self.position_end
     }
 
-    fn is_complete(&self) -> bool {
-        self.complete
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
     }
 
     fn path(&self) -> &Vec<GridPosition> {
         &self.complete_path
     }
 }
+
+impl MazeSolverDrawable for MazeSolverDFS {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    /// Cells on the stack, skipping the ones visited since they were pushed
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.current_search_positions
+            .iter()
+            .filter(|cell| !self.search_grid[cell.x as usize][cell.y as usize])
+            .copied()
+            .collect()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.current_search_positions
+            .iter()
+            .filter(|cell| !self.search_grid[cell.x as usize][cell.y as usize])
+            .count()
+    }
+
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        Some(&self.path_edges)
+    }
+}
@@ -0,0 +1,198 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    astar::{open_count, open_positions, SearchNode},
+    maze::*,
+    solver::*,
+};
+
+const STEPS_PER_UPDATE: usize = 3;
+
+/// Uniform-cost search over the weighted maze
+pub struct MazeSolverDijkstra {
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+    /// Reaching any of these ends the search, `position_end` becomes the goal that was reached
+    goals: Vec<GridPosition>,
+
+    /// Best known cost from the start position
+    search_grid: Vec<Vec<f32>>,
+
+    /// True once the cost of a cell is final
+    closed_grid: Vec<Vec<bool>>,
+
+    /// Open list of nodes to visit, ordered by g
+    current_search_positions: BinaryHeap<Reverse<SearchNode>>,
+
+    path_edges: MazePathEdges,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    expanded_nodes: Vec<GridPosition>,
+}
+
+impl MazeSolverDijkstra {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        let mut search_grid = vec![vec![f32::MAX; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[position_start.x as usize][position_start.y as usize] = 0.0;
+
+        let closed_grid = vec![vec![false; maze.grid[0].len()]; maze.grid.len()];
+
+        let mut current_search_positions = BinaryHeap::new();
+        current_search_positions.push(Reverse(SearchNode::new(0.0, 0.0, 0.0, position_start)));
+
+        let path_edges = MazePathEdges::new_with_maze(&maze);
+
+        Self {
+            maze,
+            position_start,
+            position_end,
+            goals: vec![position_end],
+            search_grid,
+            closed_grid,
+
+            current_search_positions,
+            path_edges,
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+        }
+    }
+
+    /// Search for the nearest of several goals instead, the first one is reported as the end until a goal is reached.
+    /// Panics if `goals` is empty.
+    pub fn with_goals(mut self, goals: Vec<GridPosition>) -> Self {
+        assert!(!goals.is_empty(), "Dijkstra needs at least 1 goal");
+        self.position_end = goals[0];
+        self.goals = goals;
+        self
+    }
+
+    /// 1 step of uniform-cost search, expanding the cheapest open node
+    fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+
+        // The same cell may be pushed several times, skip entries that were superseded by a cheaper one
+        let Some(start) = self.current_search_positions.pop().map(|node| node.0) else {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        };
+        if self.closed_grid[start.position.x as usize][start.position.y as usize] {
+            return;
+        }
+        self.closed_grid[start.position.x as usize][start.position.y as usize] = true;
+        self.expanded_nodes.push(start.position);
+
+        // Costs are only final once a node is expanded
+        if self.goals.contains(&start.position) {
+            self.position_end = start.position;
+            self.outcome = SearchOutcome::Found;
+            self.complete_path = self
+                .path_edges
+                .build_path(&self.position_start, &self.position_end)
+                .unwrap();
+            print_result_path(self);
+            return;
+        }
+
+        for cell in self.maze.neighbours(&start.position) {
+            if !self.closed_grid[cell.x as usize][cell.y as usize] {
+                let g = start.g + self.maze.move_cost(&start.position, &cell);
+                if g < self.search_grid[cell.x as usize][cell.y as usize] {
+                    self.search_grid[cell.x as usize][cell.y as usize] = g;
+                    self.path_edges.add_edge(&start.position, &cell);
+                    self.current_search_positions
+                        .push(Reverse(SearchNode::new(g, 0.0, g, cell)));
+                }
+            }
+        }
+    }
+}
+
+impl MazeSolver for MazeSolverDijkstra {
+    fn name(&self) -> &'static str {
+        "Dijkstra"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    fn goals(&self) -> &[GridPosition] {
+        &self.goals
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverDijkstra {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        open_positions(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn frontier_len(&self) -> usize {
+        open_count(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        let g = self.search_grid[position.x as usize][position.y as usize];
+        (g != f32::MAX).then_some(CellValues { g, h: 0.0, f: g })
+    }
+
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        Some(&self.path_edges)
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{heuristic::*, lpastar::*, maze::*, solver::*};
+
+const STEPS_PER_UPDATE: usize = 3;
+
+/// D* Lite, LPA* searching from the end towards the start.
+/// Besides repairing its search when cells change, the start can move without invalidating the cells expanded so far.
+pub struct MazeSolverDStarLite {
+    search: IncrementalSearch,
+}
+
+impl MazeSolverDStarLite {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        Self {
+            search: IncrementalSearch::new("D* Lite", maze, position_start, position_end, true),
+        }
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        if !heuristic.is_admissible(self.search.maze().connectivity) {
+            log::warn!(
+                "{:?} heuristic is not admissible with {:?} movement, D* Lite may not find the cheapest path",
+                heuristic,
+                self.search.maze().connectivity
+            );
+        }
+        self.search.set_heuristic(heuristic);
+        self
+    }
+
+    fn search(&mut self) {
+        if self.search.search() {
+            print_result_path(self);
+        }
+    }
+}
+
+impl MazeSolver for MazeSolverDStarLite {
+    fn name(&self) -> &'static str {
+        "D* Lite"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.search.outcome() == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        self.search.expanded_nodes()
+    }
+
+    fn start(&self) -> &GridPosition {
+        self.search.start()
+    }
+
+    fn end(&self) -> &GridPosition {
+        self.search.end()
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.search.outcome()
+    }
+
+    fn abort(&mut self) {
+        if self.search.outcome() == SearchOutcome::InProgress {
+            self.search.abort();
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.search.maze().path_cost(self.search.path())
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        self.search.path()
+    }
+
+    fn set_cell(&mut self, position: &GridPosition, value: u8) -> bool {
+        self.search.set_cell(position, value);
+        true
+    }
+
+    fn set_start(&mut self, position: &GridPosition) -> bool {
+        self.search.set_start(position);
+        true
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverDStarLite {
+    fn update(&mut self) {
+        if self.search.outcome() == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn drawn_expanded_nodes(&self) -> &[GridPosition] {
+        self.search.repair_expanded_nodes()
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.search.frontier()
+    }
+
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        self.search.cell_values(position)
+    }
+}
@@ -0,0 +1,310 @@
+//! Random maze generation.
+//!
+//! Mazes are carved into a grid of `2 * rows + 1` by `2 * columns + 1` cells: maze cells sit at odd
+//! coordinates and the cells between two of them are the walls that can be removed. Generators are
+//! stepped like the solvers so they can be animated.
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::maze::*;
+
+mod backtracker;
+mod eller;
+mod kruskal;
+mod prim;
+mod wilson;
+
+use self::{backtracker::*, eller::*, kruskal::*, prim::*, wilson::*};
+
+/// Maze cell coordinate, as opposed to a `GridPosition` in the carved grid
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Cell {
+    pub(crate) row: usize,
+    pub(crate) column: usize,
+}
+
+impl Cell {
+    pub(crate) fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+
+    pub(crate) fn position(&self) -> GridPosition {
+        GridPosition::new(2 * self.row as i32 + 1, 2 * self.column as i32 + 1)
+    }
+}
+
+/// Grid being carved, all walls until cells and the walls between them are removed
+pub(crate) struct CarvedGrid {
+    pub(crate) maze: Maze,
+    pub(crate) rows: usize,
+    pub(crate) columns: usize,
+}
+
+impl CarvedGrid {
+    fn new(rows: usize, columns: usize) -> Self {
+        let grid = vec![vec![CELL_WALL; 2 * columns + 1]; 2 * rows + 1];
+        let maze = Maze {
+            grid,
+            position_start: Some(Cell::new(0, 0).position()),
+            position_end: Some(Cell::new(rows - 1, columns - 1).position()),
+            connectivity: Connectivity::Four,
+        };
+
+        Self {
+            maze,
+            rows,
+            columns,
+        }
+    }
+
+    pub(crate) fn index(&self, cell: &Cell) -> usize {
+        cell.row * self.columns + cell.column
+    }
+
+    pub(crate) fn cell_at(&self, index: usize) -> Cell {
+        Cell::new(index / self.columns, index % self.columns)
+    }
+
+    pub(crate) fn is_carved(&self, cell: &Cell) -> bool {
+        let position = cell.position();
+        self.maze.grid[position.x as usize][position.y as usize] != CELL_WALL
+    }
+
+    pub(crate) fn carve(&mut self, cell: &Cell) {
+        let position = cell.position();
+        self.maze.grid[position.x as usize][position.y as usize] = CELL_EMPTY;
+    }
+
+    /// Carve both cells and the wall between them
+    pub(crate) fn connect(&mut self, from: &Cell, to: &Cell) {
+        self.carve(from);
+        self.carve(to);
+        let wall = Self::wall_between(from, to);
+        self.maze.grid[wall.x as usize][wall.y as usize] = CELL_EMPTY;
+    }
+
+    pub(crate) fn wall_between(from: &Cell, to: &Cell) -> GridPosition {
+        let from = from.position();
+        let to = to.position();
+        GridPosition::new((from.x + to.x) / 2, (from.y + to.y) / 2)
+    }
+
+    /// Orthogonally adjacent cells inside the maze
+    pub(crate) fn neighbours(&self, cell: &Cell) -> Vec<Cell> {
+        let mut neighbours = Vec::with_capacity(4);
+        if cell.row > 0 {
+            neighbours.push(Cell::new(cell.row - 1, cell.column));
+        }
+        if cell.column + 1 < self.columns {
+            neighbours.push(Cell::new(cell.row, cell.column + 1));
+        }
+        if cell.row + 1 < self.rows {
+            neighbours.push(Cell::new(cell.row + 1, cell.column));
+        }
+        if cell.column > 0 {
+            neighbours.push(Cell::new(cell.row, cell.column - 1));
+        }
+        neighbours
+    }
+}
+
+/// Single maze carving algorithm, driven by `MazeGenerator`
+pub(crate) trait MazeCarver {
+    /// 1 step of carving, returns false once the maze is a complete spanning tree
+    fn carve_step(&mut self, grid: &mut CarvedGrid, rng: &mut StdRng) -> bool;
+
+    /// Cells the carver is currently working on, highlighted by the viewer
+    fn active_cells(&self) -> Vec<Cell>;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MazeGenerateAlgorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Wilson,
+    Eller,
+}
+
+impl MazeGenerateAlgorithm {
+    pub const ALL: [Self; 5] = [
+        Self::RecursiveBacktracker,
+        Self::Prim,
+        Self::Kruskal,
+        Self::Wilson,
+        Self::Eller,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::RecursiveBacktracker => "Recursive backtracker",
+            Self::Prim => "Prim",
+            Self::Kruskal => "Kruskal",
+            Self::Wilson => "Wilson",
+            Self::Eller => "Eller",
+        }
+    }
+}
+
+impl std::str::FromStr for MazeGenerateAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "backtracker" | "recursive-backtracker" | "dfs" => Ok(Self::RecursiveBacktracker),
+            "prim" => Ok(Self::Prim),
+            "kruskal" => Ok(Self::Kruskal),
+            "wilson" => Ok(Self::Wilson),
+            "eller" => Ok(Self::Eller),
+            _ => Err(anyhow::anyhow!("Unknown generation algorithm: {}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GeneratorOptions {
+    /// Number of maze cells, the carved grid is `2 * rows + 1` by `2 * columns + 1`
+    pub rows: usize,
+    pub columns: usize,
+    pub seed: u64,
+    /// Fraction of the remaining inner walls removed after carving, 0 for a perfect maze without cycles
+    pub loop_density: f32,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            rows: 12,
+            columns: 12,
+            seed: 0,
+            loop_density: 0.0,
+        }
+    }
+}
+
+/// Steppable maze generator
+pub struct MazeGenerator {
+    algorithm: MazeGenerateAlgorithm,
+    grid: CarvedGrid,
+    carver: Box<dyn MazeCarver>,
+    rng: StdRng,
+    loop_density: f32,
+
+    complete: bool,
+}
+
+impl MazeGenerator {
+    pub fn new(algorithm: MazeGenerateAlgorithm, options: &GeneratorOptions) -> Self {
+        let rows = options.rows.max(1);
+        let columns = options.columns.max(1);
+
+        let grid = CarvedGrid::new(rows, columns);
+        let mut rng = StdRng::seed_from_u64(options.seed);
+
+        let carver: Box<dyn MazeCarver> = match algorithm {
+            MazeGenerateAlgorithm::RecursiveBacktracker => {
+                Box::new(RecursiveBacktracker::new(&grid, &mut rng))
+            }
+            MazeGenerateAlgorithm::Prim => Box::new(Prim::new(&grid, &mut rng)),
+            MazeGenerateAlgorithm::Kruskal => Box::new(Kruskal::new(&grid, &mut rng)),
+            MazeGenerateAlgorithm::Wilson => Box::new(Wilson::new(&grid, &mut rng)),
+            MazeGenerateAlgorithm::Eller => Box::new(Eller::new(&grid)),
+        };
+
+        Self {
+            algorithm,
+            grid,
+            carver,
+            rng,
+            loop_density: options.loop_density.clamp(0.0, 1.0),
+
+            complete: false,
+        }
+    }
+
+    pub fn algorithm(&self) -> MazeGenerateAlgorithm {
+        self.algorithm
+    }
+
+    /// 1 step of carving, returns false once the maze is complete
+    pub fn step(&mut self) -> bool {
+        if self.complete {
+            return false;
+        }
+
+        if !self.carver.carve_step(&mut self.grid, &mut self.rng) {
+            self.add_loops();
+            self.complete = true;
+            log::info!(
+                "Generated {}x{} maze with {} algorithm",
+                self.grid.maze.grid.len(),
+                self.grid.maze.grid[0].len(),
+                self.algorithm.name()
+            );
+        }
+        !self.complete
+    }
+
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Maze carved so far
+    pub fn maze(&self) -> &Maze {
+        &self.grid.maze
+    }
+
+    /// Grid positions the algorithm is currently working on
+    pub fn active_positions(&self) -> Vec<GridPosition> {
+        if self.complete {
+            return Vec::new();
+        }
+        self.carver
+            .active_cells()
+            .iter()
+            .map(|cell| cell.position())
+            .collect()
+    }
+
+    /// Run to completion and return the finished maze
+    pub fn generate(mut self) -> Maze {
+        self.run();
+        self.grid.maze
+    }
+
+    /// Remove a random fraction of the walls separating two cells to create cycles
+    fn add_loops(&mut self) {
+        if self.loop_density <= 0.0 {
+            return;
+        }
+
+        let mut walls = Vec::new();
+        for row in 0..self.grid.rows {
+            for column in 0..self.grid.columns {
+                let cell = Cell::new(row, column);
+                if row + 1 < self.grid.rows {
+                    walls.push(CarvedGrid::wall_between(&cell, &Cell::new(row + 1, column)));
+                }
+                if column + 1 < self.grid.columns {
+                    walls.push(CarvedGrid::wall_between(&cell, &Cell::new(row, column + 1)));
+                }
+            }
+        }
+        walls.retain(|wall| self.grid.maze.grid[wall.x as usize][wall.y as usize] == CELL_WALL);
+        walls.shuffle(&mut self.rng);
+
+        let count = (walls.len() as f32 * self.loop_density).round() as usize;
+        for wall in walls.iter().take(count) {
+            self.grid.maze.grid[wall.x as usize][wall.y as usize] = CELL_EMPTY;
+        }
+    }
+}
+
+/// Random cell of the maze
+pub(crate) fn random_cell(grid: &CarvedGrid, rng: &mut StdRng) -> Cell {
+    Cell::new(rng.gen_range(0..grid.rows), rng.gen_range(0..grid.columns))
+}
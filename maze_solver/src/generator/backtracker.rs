@@ -0,0 +1,47 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use super::*;
+
+/// Randomized depth-first search, producing long winding corridors
+pub(crate) struct RecursiveBacktracker {
+    stack: Vec<Cell>,
+}
+
+impl RecursiveBacktracker {
+    pub(crate) fn new(grid: &CarvedGrid, rng: &mut StdRng) -> Self {
+        Self {
+            stack: vec![random_cell(grid, rng)],
+        }
+    }
+}
+
+impl MazeCarver for RecursiveBacktracker {
+    fn carve_step(&mut self, grid: &mut CarvedGrid, rng: &mut StdRng) -> bool {
+        let Some(&cell) = self.stack.last() else {
+            return false;
+        };
+        grid.carve(&cell);
+
+        let unvisited = grid
+            .neighbours(&cell)
+            .into_iter()
+            .filter(|neighbour| !grid.is_carved(neighbour))
+            .collect::<Vec<_>>();
+
+        match unvisited.choose(rng) {
+            Some(next) => {
+                grid.connect(&cell, next);
+                self.stack.push(*next);
+            }
+            // Dead end, backtrack
+            None => {
+                self.stack.pop();
+            }
+        }
+        !self.stack.is_empty()
+    }
+
+    fn active_cells(&self) -> Vec<Cell> {
+        self.stack.last().copied().into_iter().collect()
+    }
+}
@@ -0,0 +1,94 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use super::*;
+
+/// Eller's algorithm, carving one row at a time while tracking which cells of the row are connected
+pub(crate) struct Eller {
+    row: usize,
+    /// Set id of each cell in the current row
+    sets: Vec<usize>,
+    next_set: usize,
+}
+
+impl Eller {
+    pub(crate) fn new(grid: &CarvedGrid) -> Self {
+        Self {
+            row: 0,
+            sets: (0..grid.columns).collect(),
+            next_set: grid.columns,
+        }
+    }
+
+    fn merge(&mut self, from: usize, to: usize) {
+        for set in &mut self.sets {
+            if *set == from {
+                *set = to;
+            }
+        }
+    }
+}
+
+impl MazeCarver for Eller {
+    /// Each step carves one row and its connections to the next row
+    fn carve_step(&mut self, grid: &mut CarvedGrid, rng: &mut StdRng) -> bool {
+        if self.row >= grid.rows {
+            return false;
+        }
+
+        let row = self.row;
+        let last_row = row + 1 == grid.rows;
+
+        for column in 0..grid.columns {
+            grid.carve(&Cell::new(row, column));
+        }
+
+        // Randomly join adjacent cells of different sets, the last row must join all of them
+        for column in 0..grid.columns.saturating_sub(1) {
+            let (left, right) = (self.sets[column], self.sets[column + 1]);
+            if left != right && (last_row || rng.gen_bool(0.5)) {
+                grid.connect(&Cell::new(row, column), &Cell::new(row, column + 1));
+                self.merge(right, left);
+            }
+        }
+
+        if !last_row {
+            // Every set continues down at least once, cells that do not continue start new sets
+            let mut next_sets = vec![None; grid.columns];
+            let mut set_ids = self.sets.clone();
+            set_ids.sort_unstable();
+            set_ids.dedup();
+
+            for set in set_ids {
+                let mut columns = (0..grid.columns)
+                    .filter(|&column| self.sets[column] == set)
+                    .collect::<Vec<_>>();
+                columns.shuffle(rng);
+
+                let down_count = rng.gen_range(1..=columns.len());
+                for &column in columns.iter().take(down_count) {
+                    grid.connect(&Cell::new(row, column), &Cell::new(row + 1, column));
+                    next_sets[column] = Some(set);
+                }
+            }
+
+            self.sets = next_sets
+                .into_iter()
+                .map(|set| {
+                    set.unwrap_or_else(|| {
+                        self.next_set += 1;
+                        self.next_set
+                    })
+                })
+                .collect();
+        }
+
+        self.row += 1;
+        self.row < grid.rows
+    }
+
+    fn active_cells(&self) -> Vec<Cell> {
+        (0..self.sets.len())
+            .map(|column| Cell::new(self.row, column))
+            .collect()
+    }
+}
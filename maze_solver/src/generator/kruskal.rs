@@ -0,0 +1,80 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use super::*;
+
+/// Randomized Kruskal's algorithm, joining disjoint sets of cells through shuffled walls
+pub(crate) struct Kruskal {
+    /// Walls between two cells, in random order
+    edges: Vec<(Cell, Cell)>,
+    /// Union-find parent indices
+    parents: Vec<usize>,
+    last_edge: Option<(Cell, Cell)>,
+}
+
+impl Kruskal {
+    pub(crate) fn new(grid: &CarvedGrid, rng: &mut StdRng) -> Self {
+        let mut edges = Vec::new();
+        for row in 0..grid.rows {
+            for column in 0..grid.columns {
+                let cell = Cell::new(row, column);
+                if row + 1 < grid.rows {
+                    edges.push((cell, Cell::new(row + 1, column)));
+                }
+                if column + 1 < grid.columns {
+                    edges.push((cell, Cell::new(row, column + 1)));
+                }
+            }
+        }
+        edges.shuffle(rng);
+
+        Self {
+            edges,
+            parents: (0..grid.rows * grid.columns).collect(),
+            last_edge: None,
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Path compression
+        let mut index = index;
+        while self.parents[index] != root {
+            let next = self.parents[index];
+            self.parents[index] = root;
+            index = next;
+        }
+        root
+    }
+}
+
+impl MazeCarver for Kruskal {
+    /// Each step removes one wall, skipping the walls between cells that are already connected
+    fn carve_step(&mut self, grid: &mut CarvedGrid, _rng: &mut StdRng) -> bool {
+        // A single cell maze has no walls to remove
+        if grid.rows * grid.columns == 1 {
+            grid.carve(&Cell::new(0, 0));
+        }
+
+        while let Some((from, to)) = self.edges.pop() {
+            let from_root = self.find(grid.index(&from));
+            let to_root = self.find(grid.index(&to));
+            if from_root != to_root {
+                self.parents[from_root] = to_root;
+                grid.connect(&from, &to);
+                self.last_edge = Some((from, to));
+                break;
+            }
+        }
+        !self.edges.is_empty()
+    }
+
+    fn active_cells(&self) -> Vec<Cell> {
+        self.last_edge
+            .map(|(from, to)| vec![from, to])
+            .unwrap_or_default()
+    }
+}
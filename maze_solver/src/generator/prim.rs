@@ -0,0 +1,61 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use super::*;
+
+/// Randomized Prim's algorithm, growing the maze from random frontier cells
+pub(crate) struct Prim {
+    /// Uncarved cells adjacent to the maze
+    frontier: Vec<Cell>,
+    in_frontier: Vec<bool>,
+}
+
+impl Prim {
+    pub(crate) fn new(grid: &CarvedGrid, rng: &mut StdRng) -> Self {
+        let in_frontier = vec![false; grid.rows * grid.columns];
+        let start = random_cell(grid, rng);
+
+        let mut prim = Self {
+            frontier: Vec::new(),
+            in_frontier,
+        };
+        prim.frontier.push(start);
+        prim.in_frontier[grid.index(&start)] = true;
+        prim
+    }
+}
+
+impl MazeCarver for Prim {
+    fn carve_step(&mut self, grid: &mut CarvedGrid, rng: &mut StdRng) -> bool {
+        if self.frontier.is_empty() {
+            return false;
+        }
+
+        let cell = self
+            .frontier
+            .swap_remove(rng.gen_range(0..self.frontier.len()));
+
+        // Connect to a random cell already in the maze, only the first cell has none
+        let neighbours = grid.neighbours(&cell);
+        let carved = neighbours
+            .iter()
+            .filter(|neighbour| grid.is_carved(neighbour))
+            .collect::<Vec<_>>();
+        match carved.choose(rng) {
+            Some(neighbour) => grid.connect(&cell, neighbour),
+            None => grid.carve(&cell),
+        }
+
+        for neighbour in neighbours {
+            let index = grid.index(&neighbour);
+            if !grid.is_carved(&neighbour) && !self.in_frontier[index] {
+                self.in_frontier[index] = true;
+                self.frontier.push(neighbour);
+            }
+        }
+        !self.frontier.is_empty()
+    }
+
+    fn active_cells(&self) -> Vec<Cell> {
+        self.frontier.clone()
+    }
+}
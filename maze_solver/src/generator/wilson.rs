@@ -0,0 +1,89 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use super::*;
+
+/// Wilson's algorithm, adding loop-erased random walks to the maze for an unbiased spanning tree
+pub(crate) struct Wilson {
+    /// Current loop-erased walk, starting from a cell outside the maze
+    walk: Vec<Cell>,
+    /// Index of each cell in the walk, erases loops when the walk crosses itself
+    walk_index: Vec<Option<usize>>,
+
+    /// Cells not yet in the maze, in random order
+    remaining: Vec<Cell>,
+    in_maze: Vec<bool>,
+}
+
+impl Wilson {
+    pub(crate) fn new(grid: &CarvedGrid, rng: &mut StdRng) -> Self {
+        let cell_count = grid.rows * grid.columns;
+        let mut remaining = (0..cell_count)
+            .map(|index| grid.cell_at(index))
+            .collect::<Vec<_>>();
+        remaining.shuffle(rng);
+
+        let mut in_maze = vec![false; cell_count];
+        // Seed the maze with a random cell
+        let first = remaining[rng.gen_range(0..remaining.len())];
+        in_maze[grid.index(&first)] = true;
+
+        Self {
+            walk: Vec::new(),
+            walk_index: vec![None; cell_count],
+            remaining,
+            in_maze,
+        }
+    }
+
+    fn push_walk(&mut self, grid: &CarvedGrid, cell: Cell) {
+        self.walk_index[grid.index(&cell)] = Some(self.walk.len());
+        self.walk.push(cell);
+    }
+}
+
+impl MazeCarver for Wilson {
+    /// Each step moves the random walk by one cell
+    fn carve_step(&mut self, grid: &mut CarvedGrid, rng: &mut StdRng) -> bool {
+        let Some(&head) = self.walk.last() else {
+            // Start a new walk from the next cell outside the maze
+            while let Some(cell) = self.remaining.pop() {
+                if !self.in_maze[grid.index(&cell)] {
+                    self.push_walk(grid, cell);
+                    return true;
+                }
+            }
+
+            // The seed cell is only carved once the maze has a single cell
+            if grid.rows * grid.columns == 1 {
+                grid.carve(&Cell::new(0, 0));
+            }
+            return false;
+        };
+
+        let neighbours = grid.neighbours(&head);
+        let next = *neighbours.choose(rng).unwrap();
+
+        if self.in_maze[grid.index(&next)] {
+            // Reached the maze, carve the walk into it
+            let mut previous = next;
+            for cell in self.walk.drain(..).rev() {
+                grid.connect(&previous, &cell);
+                self.in_maze[grid.index(&cell)] = true;
+                self.walk_index[grid.index(&cell)] = None;
+                previous = cell;
+            }
+        } else if let Some(index) = self.walk_index[grid.index(&next)] {
+            // Erase the loop back to the earlier visit
+            for cell in self.walk.drain(index + 1..) {
+                self.walk_index[grid.index(&cell)] = None;
+            }
+        } else {
+            self.push_walk(grid, next);
+        }
+        true
+    }
+
+    fn active_cells(&self) -> Vec<Cell> {
+        self.walk.clone()
+    }
+}
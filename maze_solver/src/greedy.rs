@@ -0,0 +1,190 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{astar::SearchNode, heuristic::*, maze::*, solver::*};
+
+const STEPS_PER_UPDATE: usize = 3;
+
+/// Greedy best-first search, always expanding the open node that looks closest to the goal.
+/// Usually expands far fewer nodes than A* but ignores path costs, so the path can be much longer.
+pub struct MazeSolverGreedyBestFirst {
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+
+    heuristic: Heuristic,
+
+    /// True once a cell was added to the open list
+    search_grid: Vec<Vec<bool>>,
+
+    /// Open list of nodes to visit, ordered by h
+    current_search_positions: BinaryHeap<Reverse<SearchNode>>,
+
+    path_edges: MazePathEdges,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    expanded_nodes: Vec<GridPosition>,
+}
+
+impl MazeSolverGreedyBestFirst {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        let mut search_grid = vec![vec![false; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[position_start.x as usize][position_start.y as usize] = true;
+
+        let heuristic = Heuristic::default_for(maze.connectivity);
+        let h = heuristic.distance(&position_start, &position_end);
+
+        let mut current_search_positions = BinaryHeap::new();
+        current_search_positions.push(Reverse(SearchNode::new(0.0, h, h, position_start)));
+
+        let path_edges = MazePathEdges::new_with_maze(&maze);
+
+        Self {
+            maze,
+            position_start,
+            position_end,
+            heuristic,
+            search_grid,
+
+            current_search_positions,
+            path_edges,
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+        }
+    }
+
+    /// Admissibility does not matter, greedy search gives no cost guarantee with any heuristic
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        let h = self.h(&self.position_start);
+        self.current_search_positions.clear();
+        self.current_search_positions.push(Reverse(SearchNode::new(
+            0.0,
+            h,
+            h,
+            self.position_start,
+        )));
+        self
+    }
+
+    /// 1 step of greedy search, expanding the open node with the lowest h by 1
+    fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+
+        let Some(start) = self.current_search_positions.pop().map(|node| node.0) else {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        };
+        self.expanded_nodes.push(start.position);
+
+        // Only reached when starting on the goal, other cells are tested when generated
+        if start.position == self.position_end {
+            self.outcome = SearchOutcome::Found;
+            self.complete_path = vec![start.position];
+            print_result_path(self);
+            return;
+        }
+
+        for cell in self.maze.neighbours(&start.position) {
+            if self.search_grid[cell.x as usize][cell.y as usize] {
+                continue;
+            }
+            self.search_grid[cell.x as usize][cell.y as usize] = true;
+            self.path_edges.add_edge(&start.position, &cell);
+
+            if cell == self.position_end {
+                self.outcome = SearchOutcome::Found;
+                self.complete_path = self
+                    .path_edges
+                    .build_path(&self.position_start, &self.position_end)
+                    .unwrap();
+                print_result_path(self);
+                return;
+            }
+
+            let g = start.g + self.maze.move_cost(&start.position, &cell);
+            let h = self.h(&cell);
+            self.current_search_positions
+                .push(Reverse(SearchNode::new(g, h, h, cell)));
+        }
+    }
+
+    /// Heuristic function - distance from end position to position parameter
+    fn h(&self, position: &GridPosition) -> f32 {
+        self.heuristic.distance(position, &self.position_end)
+    }
+}
+
+impl MazeSolver for MazeSolverGreedyBestFirst {
+    fn name(&self) -> &'static str {
+        "Greedy best-first"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverGreedyBestFirst {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.current_search_positions
+            .iter()
+            .map(|node| node.0.position)
+            .collect()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.current_search_positions.len()
+    }
+
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        Some(&self.path_edges)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::maze::*;
+
+/// Distance estimate from a cell to the goal for informed searches
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Heuristic {
+    Manhattan,
+    Euclidean,
+    Octile,
+    Chebyshev,
+    /// Turns A* into uniform-cost search
+    Zero,
+}
+
+impl Heuristic {
+    pub const ALL: [Self; 5] = [
+        Self::Manhattan,
+        Self::Euclidean,
+        Self::Octile,
+        Self::Chebyshev,
+        Self::Zero,
+    ];
+
+    /// Tightest admissible heuristic for the movement model
+    pub fn default_for(connectivity: Connectivity) -> Self {
+        match connectivity {
+            Connectivity::Four => Self::Manhattan,
+            Connectivity::Eight(_) => Self::Octile,
+        }
+    }
+
+    pub fn distance(&self, from: &GridPosition, to: &GridPosition) -> f32 {
+        let dx = (from.x - to.x).abs() as f32;
+        let dy = (from.y - to.y).abs() as f32;
+
+        match self {
+            Self::Manhattan => dx + dy,
+            Self::Euclidean => (dx * dx + dy * dy).sqrt(),
+            Self::Octile => dx.max(dy) + (std::f32::consts::SQRT_2 - 1.0) * dx.min(dy),
+            Self::Chebyshev => dx.max(dy),
+            Self::Zero => 0.0,
+        }
+    }
+
+    /// Whether the heuristic never overestimates under the movement model.
+    /// All cells cost at least 1 so only the geometry matters.
+    pub fn is_admissible(&self, connectivity: Connectivity) -> bool {
+        match connectivity {
+            Connectivity::Four => true,
+            Connectivity::Eight(_) => *self != Self::Manhattan,
+        }
+    }
+}
+
+impl std::str::FromStr for Heuristic {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "manhattan" => Ok(Self::Manhattan),
+            "euclidean" => Ok(Self::Euclidean),
+            "octile" => Ok(Self::Octile),
+            "chebyshev" => Ok(Self::Chebyshev),
+            "zero" => Ok(Self::Zero),
+            _ => Err(anyhow::anyhow!("Unknown heuristic: {}", s)),
+        }
+    }
+}
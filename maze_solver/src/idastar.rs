@@ -0,0 +1,395 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{heuristic::*, maze::*, solver::*};
+
+const STEPS_PER_UPDATE: usize = 5;
+
+/// Cells whose cheapest g of the current iteration is remembered by default
+pub const DEFAULT_MAX_CACHED_NODES: usize = 1 << 16;
+
+/// Most recent expansions kept for drawing, older ones are only counted
+const MAX_RECENT_EXPANDED_NODES: usize = 1 << 12;
+
+/// Cell on the current depth-first path with the neighbours still to be tried
+struct SearchFrame {
+    position: GridPosition,
+    g: f32,
+    neighbours: Vec<GridPosition>,
+    next_neighbour: usize,
+}
+
+/// Depth-first search bounded by f = g + h, repeated with the bound raised to the smallest f that exceeded it.
+/// Shared by IDA* and IDDFS, which counts moves instead of terrain costs and uses no heuristic.
+pub(crate) struct DeepeningSearch {
+    /// Solver name for logging the iterations
+    name: &'static str,
+
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+
+    heuristic: Heuristic,
+    /// Every move costs 1 regardless of terrain
+    unit_cost: bool,
+
+    /// Bound of the current iteration and of every iteration before it
+    thresholds: Vec<f32>,
+    /// Smallest f that exceeded the current bound, the bound of the next iteration
+    next_threshold: f32,
+
+    /// Cheapest g of up to `max_cached_nodes` cells reached in the current iteration, later visits that are no
+    /// cheaper are pruned. Cells are no longer added once it is full, which only costs repeated work.
+    g_cache: HashMap<GridPosition, f32>,
+    max_cached_nodes: usize,
+
+    /// Current start -> n path and its cells, which a path must not revisit
+    stack: Vec<SearchFrame>,
+    on_path: HashSet<GridPosition>,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    /// Expansions of all iterations, cells are counted again in every iteration that reaches them
+    expanded_count: usize,
+    iteration_expanded_count: usize,
+    /// Between half and all of the last `MAX_RECENT_EXPANDED_NODES` expansions of the current iteration
+    recent_expanded_nodes: Vec<GridPosition>,
+}
+
+impl DeepeningSearch {
+    pub(crate) fn new(
+        name: &'static str,
+        maze: Maze,
+        position_start: GridPosition,
+        position_end: GridPosition,
+        heuristic: Heuristic,
+        unit_cost: bool,
+    ) -> Self {
+        Self {
+            name,
+            maze,
+            position_start,
+            position_end,
+            heuristic,
+            unit_cost,
+
+            thresholds: Vec::new(),
+            next_threshold: heuristic.distance(&position_start, &position_end),
+
+            g_cache: HashMap::new(),
+            max_cached_nodes: DEFAULT_MAX_CACHED_NODES,
+
+            stack: Vec::new(),
+            on_path: HashSet::new(),
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_count: 0,
+            iteration_expanded_count: 0,
+            recent_expanded_nodes: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_max_cached_nodes(&mut self, max_cached_nodes: usize) {
+        self.max_cached_nodes = max_cached_nodes;
+    }
+
+    pub(crate) fn set_heuristic(&mut self, heuristic: Heuristic) {
+        self.heuristic = heuristic;
+        self.next_threshold = heuristic.distance(&self.position_start, &self.position_end);
+    }
+
+    /// 1 step of search, expanding the next node on the depth-first path or starting a new iteration.
+    /// Returns true if the search finished in this step.
+    pub(crate) fn search(&mut self) -> bool {
+        if self.outcome != SearchOutcome::InProgress {
+            return false;
+        }
+
+        if self.stack.is_empty() {
+            self.start_iteration();
+            return self.outcome != SearchOutcome::InProgress;
+        }
+
+        while let Some(frame) = self.stack.last_mut() {
+            let Some(&cell) = frame.neighbours.get(frame.next_neighbour) else {
+                self.on_path.remove(&frame.position);
+                self.stack.pop();
+                continue;
+            };
+            frame.next_neighbour += 1;
+
+            let g = frame.g
+                + if self.unit_cost {
+                    1.0
+                } else {
+                    self.maze.move_cost(&frame.position, &cell)
+                };
+            if self.visit(cell, g) {
+                break;
+            }
+        }
+
+        if self.stack.is_empty() && self.outcome == SearchOutcome::InProgress {
+            log::info!(
+                "{} iteration with bound {} expanded {} nodes",
+                self.name,
+                self.thresholds.last().unwrap(),
+                self.iteration_expanded_count
+            );
+        }
+        self.outcome != SearchOutcome::InProgress
+    }
+
+    /// Restart from the start position with the next bound, the goal is unreachable if no f exceeded the last one
+    fn start_iteration(&mut self) {
+        if self.next_threshold == f32::MAX {
+            self.outcome = SearchOutcome::Unreachable;
+            return;
+        }
+
+        self.thresholds.push(self.next_threshold);
+        self.next_threshold = f32::MAX;
+        log::info!(
+            "{} iteration {} with bound {}",
+            self.name,
+            self.thresholds.len(),
+            self.thresholds.last().unwrap()
+        );
+
+        self.g_cache.clear();
+        self.iteration_expanded_count = 0;
+        self.recent_expanded_nodes.clear();
+
+        self.visit(self.position_start, 0.0);
+    }
+
+    /// Push a cell onto the path unless it exceeds the bound or was reached more cheaply before, returns true if expanded
+    fn visit(&mut self, position: GridPosition, g: f32) -> bool {
+        let threshold = *self.thresholds.last().unwrap();
+        let f = g + self.heuristic.distance(&position, &self.position_end);
+        if f > threshold {
+            self.next_threshold = self.next_threshold.min(f);
+            return false;
+        }
+
+        if self.on_path.contains(&position)
+            || self
+                .g_cache
+                .get(&position)
+                .is_some_and(|&cached| g >= cached)
+        {
+            return false;
+        }
+        if self.g_cache.len() < self.max_cached_nodes || self.g_cache.contains_key(&position) {
+            self.g_cache.insert(position, g);
+        }
+
+        self.expanded_count += 1;
+        self.iteration_expanded_count += 1;
+        // Dropping the older half at once keeps pushes amortised O(1)
+        if self.recent_expanded_nodes.len() == MAX_RECENT_EXPANDED_NODES {
+            self.recent_expanded_nodes
+                .drain(..MAX_RECENT_EXPANDED_NODES / 2);
+        }
+        self.recent_expanded_nodes.push(position);
+
+        if position == self.position_end {
+            self.outcome = SearchOutcome::Found;
+            self.complete_path = self.stack.iter().map(|frame| frame.position).collect();
+            self.complete_path.push(position);
+            self.stack.clear();
+            self.on_path.clear();
+            return true;
+        }
+
+        self.on_path.insert(position);
+        self.stack.push(SearchFrame {
+            position,
+            g,
+            neighbours: self.maze.neighbours(&position).collect(),
+            next_neighbour: 0,
+        });
+        true
+    }
+
+    pub(crate) fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    pub(crate) fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    pub(crate) fn abort(&mut self) {
+        self.outcome = SearchOutcome::Aborted;
+        self.stack.clear();
+        self.on_path.clear();
+    }
+
+    pub(crate) fn thresholds(&self) -> &[f32] {
+        &self.thresholds
+    }
+
+    pub(crate) fn expanded_count(&self) -> usize {
+        self.expanded_count
+    }
+
+    pub(crate) fn recent_expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.recent_expanded_nodes
+    }
+
+    /// Neighbours of the cells on the current path that are yet to be tried
+    pub(crate) fn frontier(&self) -> Vec<GridPosition> {
+        self.stack
+            .iter()
+            .flat_map(|frame| frame.neighbours[frame.next_neighbour..].iter().copied())
+            .collect()
+    }
+
+    pub(crate) fn frontier_len(&self) -> usize {
+        self.stack
+            .iter()
+            .map(|frame| frame.neighbours.len() - frame.next_neighbour)
+            .sum()
+    }
+
+    /// Values of the current iteration, for the cells the g cache holds
+    pub(crate) fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        let g = *self.g_cache.get(position)?;
+        let h = self.heuristic.distance(position, &self.position_end);
+        Some(CellValues { g, h, f: g + h })
+    }
+
+    pub(crate) fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    pub(crate) fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    pub(crate) fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+/// Iterative deepening A*, optimal like A* while only storing the current path and a bounded cache of g values
+pub struct MazeSolverIDAStar {
+    search: DeepeningSearch,
+}
+
+impl MazeSolverIDAStar {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        let heuristic = Heuristic::default_for(maze.connectivity);
+        Self {
+            search: DeepeningSearch::new(
+                "IDA*",
+                maze,
+                position_start,
+                position_end,
+                heuristic,
+                false,
+            ),
+        }
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        if !heuristic.is_admissible(self.search.maze().connectivity) {
+            log::warn!(
+                "{:?} heuristic is not admissible with {:?} movement, IDA* may not find the cheapest path",
+                heuristic,
+                self.search.maze().connectivity
+            );
+        }
+        self.search.set_heuristic(heuristic);
+        self
+    }
+
+    /// Remember the cheapest g of at most this many cells per iteration, 0 only prevents cycles along the path.
+    /// A smaller cache uses less memory but revisits more cells.
+    pub fn with_max_cached_nodes(mut self, max_cached_nodes: usize) -> Self {
+        self.search.set_max_cached_nodes(max_cached_nodes);
+        self
+    }
+
+    fn search(&mut self) {
+        if self.search.search() {
+            print_result_path(self);
+        }
+    }
+}
+
+impl MazeSolver for MazeSolverIDAStar {
+    fn name(&self) -> &'static str {
+        "IDA*"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.search.outcome() == SearchOutcome::InProgress
+    }
+
+    /// Latest expansions of the current iteration
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        self.search.recent_expanded_nodes()
+    }
+
+    fn expanded_count(&self) -> usize {
+        self.search.expanded_count()
+    }
+
+    fn thresholds(&self) -> &[f32] {
+        self.search.thresholds()
+    }
+
+    fn start(&self) -> &GridPosition {
+        self.search.start()
+    }
+
+    fn end(&self) -> &GridPosition {
+        self.search.end()
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.search.outcome()
+    }
+
+    fn abort(&mut self) {
+        if self.search.outcome() == SearchOutcome::InProgress {
+            self.search.abort();
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.search.maze().path_cost(self.search.path())
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        self.search.path()
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverIDAStar {
+    fn update(&mut self) {
+        if self.search.outcome() == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.search.frontier()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.search.frontier_len()
+    }
+
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        self.search.cell_values(position)
+    }
+}
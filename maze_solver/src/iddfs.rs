@@ -0,0 +1,109 @@
+use crate::{heuristic::*, idastar::DeepeningSearch, maze::*, solver::*};
+
+const STEPS_PER_UPDATE: usize = 5;
+
+/// Iterative deepening DFS, finds a path with the fewest moves while only storing the current path and a bounded
+/// cache of depths
+pub struct MazeSolverIDDFS {
+    search: DeepeningSearch,
+}
+
+impl MazeSolverIDDFS {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        Self {
+            search: DeepeningSearch::new(
+                "IDDFS",
+                maze,
+                position_start,
+                position_end,
+                Heuristic::Zero,
+                true,
+            ),
+        }
+    }
+
+    /// Remember the smallest depth of at most this many cells per iteration, 0 only prevents cycles along the path
+    pub fn with_max_cached_nodes(mut self, max_cached_nodes: usize) -> Self {
+        self.search.set_max_cached_nodes(max_cached_nodes);
+        self
+    }
+
+    fn search(&mut self) {
+        if self.search.search() {
+            print_result_path(self);
+        }
+    }
+}
+
+impl MazeSolver for MazeSolverIDDFS {
+    fn name(&self) -> &'static str {
+        "IDDFS"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.search.outcome() == SearchOutcome::InProgress
+    }
+
+    /// Latest expansions of the current iteration
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        self.search.recent_expanded_nodes()
+    }
+
+    fn expanded_count(&self) -> usize {
+        self.search.expanded_count()
+    }
+
+    fn thresholds(&self) -> &[f32] {
+        self.search.thresholds()
+    }
+
+    fn start(&self) -> &GridPosition {
+        self.search.start()
+    }
+
+    fn end(&self) -> &GridPosition {
+        self.search.end()
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.search.outcome()
+    }
+
+    fn abort(&mut self) {
+        if self.search.outcome() == SearchOutcome::InProgress {
+            self.search.abort();
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.search.maze().path_cost(self.search.path())
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        self.search.path()
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverIDDFS {
+    fn update(&mut self) {
+        if self.search.outcome() == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.search.frontier()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.search.frontier_len()
+    }
+
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        self.search.cell_values(position)
+    }
+}
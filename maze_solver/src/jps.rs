@@ -0,0 +1,414 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    astar::{open_count, open_positions, SearchNode},
+    heuristic::*,
+    maze::*,
+    solver::*,
+};
+
+const STEPS_PER_UPDATE: usize = 1;
+
+/// Jump Point Search, A* over the jump points of a uniform-cost grid.
+/// Straight and diagonal runs without branching decisions are skipped in one jump instead of expanding every cell.
+pub struct MazeSolverJPS {
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+
+    heuristic: Heuristic,
+
+    /// Best known g(n) values of jump points
+    search_grid: Vec<Vec<f32>>,
+
+    /// True once a jump point has been expanded with its best known g(n)
+    closed_grid: Vec<Vec<bool>>,
+
+    /// Open list of jump points to visit
+    current_search_positions: BinaryHeap<Reverse<SearchNode>>,
+
+    /// Edges between jump points, the cells in between are filled in when building the path
+    path_edges: MazePathEdges,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    expanded_nodes: Vec<GridPosition>,
+    /// Cells stepped over by jumps, including the ones of branches that found nothing
+    scanned_cells: usize,
+    /// Every jump point found so far, expanded or not
+    jump_points: Vec<GridPosition>,
+}
+
+impl MazeSolverJPS {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        if maze.max_cell_cost() > 1.0 {
+            log::warn!(
+                "Jump Point Search treats terrain as empty cells, the path may not be the cheapest"
+            );
+        }
+
+        let mut search_grid = vec![vec![f32::MAX; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[position_start.x as usize][position_start.y as usize] = 0.0;
+
+        let closed_grid = vec![vec![false; maze.grid[0].len()]; maze.grid.len()];
+
+        let heuristic = Heuristic::default_for(maze.connectivity);
+        let h = heuristic.distance(&position_start, &position_end);
+
+        let mut current_search_positions = BinaryHeap::new();
+        current_search_positions.push(Reverse(SearchNode::new(0.0, h, h, position_start)));
+
+        let path_edges = MazePathEdges::new_with_maze(&maze);
+
+        Self {
+            maze,
+            position_start,
+            position_end,
+            heuristic,
+            search_grid,
+            closed_grid,
+
+            current_search_positions,
+            path_edges,
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+            scanned_cells: 0,
+            jump_points: vec![position_start],
+        }
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        if !heuristic.is_admissible(self.maze.connectivity) {
+            log::warn!(
+                "{:?} heuristic is not admissible with {:?} movement, JPS may not find the cheapest path",
+                heuristic,
+                self.maze.connectivity
+            );
+        }
+        self.heuristic = heuristic;
+        let h = self
+            .heuristic
+            .distance(&self.position_start, &self.position_end);
+        self.current_search_positions.clear();
+        self.current_search_positions.push(Reverse(SearchNode::new(
+            0.0,
+            h,
+            h,
+            self.position_start,
+        )));
+        self
+    }
+
+    /// 1 step of search, expanding the open jump point with the lowest f by 1
+    fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+
+        let Some(start) = self.current_search_positions.pop().map(|node| node.0) else {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        };
+
+        // A jump point is pushed again whenever a cheaper path to it is found, skip the superseded entries
+        if start.g > self.search_grid[start.position.x as usize][start.position.y as usize]
+            || self.closed_grid[start.position.x as usize][start.position.y as usize]
+        {
+            return;
+        }
+        self.closed_grid[start.position.x as usize][start.position.y as usize] = true;
+        self.expanded_nodes.push(start.position);
+
+        if start.position == self.position_end {
+            self.outcome = SearchOutcome::Found;
+            let jump_points = self
+                .path_edges
+                .build_path(&self.position_start, &self.position_end)
+                .unwrap();
+            self.complete_path = Self::fill_path(&jump_points);
+            print_result_path(self);
+            return;
+        }
+
+        let parent = (start.position != self.position_start)
+            .then(|| self.path_edges.edges[start.position.x as usize][start.position.y as usize]);
+
+        for direction in self.successor_directions(&start.position, parent.as_ref()) {
+            let Some(jump_point) = self.jump(&start.position, &direction) else {
+                continue;
+            };
+
+            let g = start.g + Self::distance(&start.position, &jump_point);
+            if g < self.search_grid[jump_point.x as usize][jump_point.y as usize] {
+                if self.search_grid[jump_point.x as usize][jump_point.y as usize] == f32::MAX {
+                    self.jump_points.push(jump_point);
+                }
+                self.search_grid[jump_point.x as usize][jump_point.y as usize] = g;
+                self.closed_grid[jump_point.x as usize][jump_point.y as usize] = false;
+                self.path_edges.add_edge(&start.position, &jump_point);
+
+                let h = self.heuristic.distance(&jump_point, &self.position_end);
+                self.current_search_positions.push(Reverse(SearchNode::new(
+                    g,
+                    h,
+                    g + h,
+                    jump_point,
+                )));
+            }
+        }
+    }
+
+    /// Directions worth jumping in from a jump point reached from `parent`, all valid moves for the start position
+    fn successor_directions(
+        &self,
+        position: &GridPosition,
+        parent: Option<&GridPosition>,
+    ) -> Vec<GridPosition> {
+        let Some(parent) = parent else {
+            return self
+                .moves()
+                .filter(|delta| self.maze.is_move_valid(position, delta))
+                .collect();
+        };
+        let direction = Self::direction(parent, position);
+        let [left, right] = Self::sides(&direction);
+
+        let natural = match self.maze.connectivity {
+            // Paths move vertically first, so vertical moves may turn anywhere and horizontal ones only at walls
+            Connectivity::Four if direction.x != 0 => [Some(direction), Some(left), Some(right)],
+            Connectivity::Four => [Some(direction), None, None],
+            Connectivity::Eight(_) if direction.x != 0 && direction.y != 0 => [
+                Some(direction),
+                Some(GridPosition::new(direction.x, 0)),
+                Some(GridPosition::new(0, direction.y)),
+            ],
+            // Without corner cutting the diagonals ahead can only be taken from the cell itself
+            Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls) => [
+                Some(direction),
+                Some(direction + left),
+                Some(direction + right),
+            ],
+            Connectivity::Eight(_) => [Some(direction), None, None],
+        };
+
+        natural
+            .into_iter()
+            .chain(self.forced_moves(position, &direction))
+            .flatten()
+            .filter(|delta| self.maze.is_move_valid(position, delta))
+            .collect()
+    }
+
+    /// Move from `position` in `direction` until reaching the goal or a jump point, None if a wall is hit first
+    fn jump(&mut self, position: &GridPosition, direction: &GridPosition) -> Option<GridPosition> {
+        let mut current = *position;
+        loop {
+            if !self.maze.is_move_valid(&current, direction) {
+                return None;
+            }
+            current = current + *direction;
+            self.scanned_cells += 1;
+
+            if current == self.position_end || self.has_forced_neighbours(&current, direction) {
+                return Some(current);
+            }
+
+            // Runs that can turn at every cell stop wherever one of their straight branches finds a jump point.
+            // Branches are straight runs themselves, so this recurses at most once.
+            let branches = match self.maze.connectivity {
+                Connectivity::Four if direction.x != 0 => Self::sides(direction),
+                Connectivity::Eight(_) if direction.x != 0 && direction.y != 0 => [
+                    GridPosition::new(direction.x, 0),
+                    GridPosition::new(0, direction.y),
+                ],
+                _ => continue,
+            };
+            if self.jump(&current, &branches[0]).is_some()
+                || self.jump(&current, &branches[1]).is_some()
+            {
+                return Some(current);
+            }
+        }
+    }
+
+    /// Whether a cell entered in `direction` has a neighbour that only an optimal path through the cell reaches
+    fn has_forced_neighbours(&self, position: &GridPosition, direction: &GridPosition) -> bool {
+        self.forced_moves(position, direction)
+            .into_iter()
+            .flatten()
+            .any(|delta| self.maze.is_move_valid(position, &delta))
+    }
+
+    /// Moves to the neighbours a cell entered in `direction` may force, decided by the 2 cells next to the move
+    /// that could block a shortcut around the cell. Whether the moves are valid is left to the caller.
+    fn forced_moves(
+        &self,
+        position: &GridPosition,
+        direction: &GridPosition,
+    ) -> [Option<GridPosition>; 2] {
+        let blocked = |delta: GridPosition| !self.maze.is_cell_valid(&(*position + delta));
+        let behind = GridPosition::new(-direction.x, -direction.y);
+
+        match self.maze.connectivity {
+            Connectivity::Four if direction.x != 0 => [None, None],
+            // Diagonals can't cut the corner, so a side cell is forced when the cell behind it is blocked
+            Connectivity::Four | Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls) => {
+                if direction.x != 0 && direction.y != 0 {
+                    return [None, None];
+                }
+                Self::sides(direction).map(|side| blocked(side + behind).then_some(side))
+            }
+            // Diagonals cut the corner, so the cell past a blocked side cell is forced
+            Connectivity::Eight(_) if direction.x != 0 && direction.y != 0 => [
+                blocked(GridPosition::new(behind.x, 0))
+                    .then_some(GridPosition::new(behind.x, direction.y)),
+                blocked(GridPosition::new(0, behind.y))
+                    .then_some(GridPosition::new(direction.x, behind.y)),
+            ],
+            Connectivity::Eight(_) => {
+                Self::sides(direction).map(|side| blocked(side).then_some(side + *direction))
+            }
+        }
+    }
+
+    /// The 2 moves perpendicular to a straight direction
+    fn sides(direction: &GridPosition) -> [GridPosition; 2] {
+        [
+            GridPosition::new(direction.y, direction.x),
+            GridPosition::new(-direction.y, -direction.x),
+        ]
+    }
+
+    /// Moves allowed by the maze connectivity
+    fn moves(&self) -> impl Iterator<Item = GridPosition> {
+        let diagonals: &[GridPosition] = match self.maze.connectivity {
+            Connectivity::Four => &[],
+            Connectivity::Eight(_) => &DIAGONAL_SEARCH_ORDER,
+        };
+        SEARCH_ORDER.into_iter().chain(diagonals.iter().copied())
+    }
+
+    fn direction(from: &GridPosition, to: &GridPosition) -> GridPosition {
+        GridPosition::new((to.x - from.x).signum(), (to.y - from.y).signum())
+    }
+
+    fn step_cost(delta: &GridPosition) -> f32 {
+        if delta.x != 0 && delta.y != 0 {
+            std::f32::consts::SQRT_2
+        } else {
+            1.0
+        }
+    }
+
+    /// Cost of a straight or diagonal jump
+    fn distance(from: &GridPosition, to: &GridPosition) -> f32 {
+        let steps = (to.x - from.x).abs().max((to.y - from.y).abs());
+        steps as f32 * Self::step_cost(&Self::direction(from, to))
+    }
+
+    /// Expand a path of jump points into every cell along it
+    fn fill_path(jump_points: &[GridPosition]) -> Vec<GridPosition> {
+        let mut path = vec![jump_points[0]];
+        for segment in jump_points.windows(2) {
+            let direction = Self::direction(&segment[0], &segment[1]);
+            let mut current = segment[0];
+            while current != segment[1] {
+                current = current + direction;
+                path.push(current);
+            }
+        }
+        path
+    }
+}
+
+impl MazeSolver for MazeSolverJPS {
+    fn name(&self) -> &'static str {
+        "JPS"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    fn scanned_cells(&self) -> Option<usize> {
+        Some(self.scanned_cells)
+    }
+
+    fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverJPS {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn highlighted_nodes(&self) -> &[GridPosition] {
+        &self.jump_points
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        open_positions(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn frontier_len(&self) -> usize {
+        open_count(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        let g = self.search_grid[position.x as usize][position.y as usize];
+        let h = self.heuristic.distance(position, &self.position_end);
+        (g != f32::MAX).then_some(CellValues { g, h, f: g + h })
+    }
+
+    /// Jump points point back to the previous jump point
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        Some(&self.path_edges)
+    }
+}
@@ -0,0 +1,55 @@
+//! Grid maze pathfinding solvers.
+//!
+//! The solvers are rendering agnostic and can be embedded directly. The ggez based
+//! viewer is only compiled with the `gui` feature.
+
+pub mod astar;
+pub mod beam;
+pub mod bfs;
+pub mod bidirectional_astar;
+pub mod bidirectional_bfs;
+pub mod dfs;
+pub mod dijkstra;
+pub mod dstar_lite;
+pub mod generator;
+pub mod greedy;
+pub mod heuristic;
+pub mod idastar;
+pub mod iddfs;
+pub mod jps;
+pub mod lpastar;
+pub mod mapf;
+pub mod maze;
+pub mod multi_goal;
+pub mod parser;
+pub mod report;
+pub mod solver;
+pub mod theta;
+
+#[cfg(feature = "gui")]
+pub mod renderer;
+#[cfg(feature = "gui")]
+pub mod viewer;
+
+pub use astar::MazeSolverAStar;
+pub use beam::MazeSolverBeam;
+pub use bfs::MazeSolverBFS;
+pub use bidirectional_astar::MazeSolverBidirectionalAStar;
+pub use bidirectional_bfs::MazeSolverBidirectionalBFS;
+pub use dfs::MazeSolverDFS;
+pub use dijkstra::MazeSolverDijkstra;
+pub use dstar_lite::MazeSolverDStarLite;
+pub use generator::{GeneratorOptions, MazeGenerateAlgorithm, MazeGenerator};
+pub use greedy::MazeSolverGreedyBestFirst;
+pub use heuristic::Heuristic;
+pub use idastar::MazeSolverIDAStar;
+pub use iddfs::MazeSolverIDDFS;
+pub use jps::MazeSolverJPS;
+pub use lpastar::MazeSolverLPAStar;
+pub use mapf::{Agent, MapfAlgorithm, MapfSolver};
+pub use maze::{Connectivity, DiagonalMovement, GridPosition, Maze, MazePathEdges};
+pub use multi_goal::{GoalMode, MazeSolverAllGoals};
+pub use solver::{
+    MazeSolveAlgorithm, MazeSolver, MazeSolverDrawable, SearchOutcome, SolverOptions,
+};
+pub use theta::MazeSolverThetaStar;
@@ -0,0 +1,494 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{heuristic::*, maze::*, solver::*};
+
+const STEPS_PER_UPDATE: usize = 3;
+
+/// Open list element, ordered by the key [min(g, rhs) + h + km, min(g, rhs)] it was pushed with
+#[derive(Clone, Copy)]
+struct QueueEntry {
+    key: (f32, f32),
+    position: GridPosition,
+}
+
+impl Eq for QueueEntry {}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        compare_keys(self.key, other.key)
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+fn compare_keys(a: (f32, f32), b: (f32, f32)) -> std::cmp::Ordering {
+    a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+}
+
+/// Key comparison for the termination test. The km offset of D* Lite accumulates rounding errors,
+/// primary keys only differing by those are compared by their secondary key instead.
+fn compare_keys_rounded(a: (f32, f32), b: (f32, f32)) -> std::cmp::Ordering {
+    let tolerance = 1e-5 * a.0.abs().max(b.0.abs());
+    if (a.0 - b.0).abs() <= tolerance {
+        a.1.total_cmp(&b.1)
+    } else {
+        a.0.total_cmp(&b.0)
+    }
+}
+
+/// Lifelong planning search, which keeps its g(n) values after finishing and only re-expands the cells whose cost
+/// changed when the maze does. A cell is consistent when g(n) equals rhs(n), the cost through its best neighbour.
+/// Shared by LPA*, searching from the start, and D* Lite, searching from the end so the start can move.
+pub(crate) struct IncrementalSearch {
+    /// Solver name for logging the repairs
+    name: &'static str,
+
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+
+    heuristic: Heuristic,
+    /// Search from the end towards the start
+    backward: bool,
+    /// Heuristic distance the start moved so far, added to new keys so queued ones remain lower bounds
+    km: f32,
+
+    /// Cost of the cell when it was last expanded
+    search_grid: Vec<Vec<f32>>,
+    /// One step lookahead cost through the best neighbour
+    rhs_grid: Vec<Vec<f32>>,
+
+    /// Inconsistent cells, entries outdated by later changes are skipped or re-queued when popped
+    current_search_positions: BinaryHeap<Reverse<QueueEntry>>,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    /// Expansions of the initial search and all repairs
+    expanded_nodes: Vec<GridPosition>,
+    /// Expansions since the maze or start position last changed
+    repair_expanded_nodes: Vec<GridPosition>,
+}
+
+impl IncrementalSearch {
+    pub(crate) fn new(
+        name: &'static str,
+        maze: Maze,
+        position_start: GridPosition,
+        position_end: GridPosition,
+        backward: bool,
+    ) -> Self {
+        let search_grid = vec![vec![f32::MAX; maze.grid[0].len()]; maze.grid.len()];
+        let rhs_grid = search_grid.clone();
+
+        let mut search = Self {
+            name,
+            heuristic: Heuristic::default_for(maze.connectivity),
+            maze,
+            position_start,
+            position_end,
+            backward,
+            km: 0.0,
+
+            search_grid,
+            rhs_grid,
+            current_search_positions: BinaryHeap::new(),
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+            repair_expanded_nodes: Vec::new(),
+        };
+        search.reset_open_list();
+        search
+    }
+
+    pub(crate) fn set_heuristic(&mut self, heuristic: Heuristic) {
+        self.heuristic = heuristic;
+        self.reset_open_list();
+    }
+
+    fn reset_open_list(&mut self) {
+        let root = self.root();
+        self.rhs_grid[root.x as usize][root.y as usize] = 0.0;
+        self.current_search_positions.clear();
+        self.push(root);
+    }
+
+    /// 1 step of search, expanding the inconsistent cell with the lowest key.
+    /// Returns true if the search finished in this step.
+    pub(crate) fn search(&mut self) -> bool {
+        if self.outcome != SearchOutcome::InProgress {
+            return false;
+        }
+
+        // Drop entries of cells made consistent since, and re-queue those whose key changed, e.g. as the start moved
+        let mut top = None;
+        while let Some(&Reverse(entry)) = self.current_search_positions.peek() {
+            let key = self.key(&entry.position);
+            if self.is_consistent(&entry.position) {
+                self.current_search_positions.pop();
+            } else if compare_keys(entry.key, key).is_ne() {
+                self.current_search_positions.pop();
+                self.current_search_positions
+                    .push(Reverse(QueueEntry { key, ..entry }));
+            } else {
+                top = Some(entry);
+                break;
+            }
+        }
+
+        let target = self.target();
+        let target_settled = top
+            .is_none_or(|entry| compare_keys_rounded(entry.key, self.key(&target)).is_ge())
+            && self.is_consistent(&target);
+        if target_settled {
+            self.finish();
+            return true;
+        }
+
+        let position = top.unwrap().position;
+        self.current_search_positions.pop();
+        self.expanded_nodes.push(position);
+        self.repair_expanded_nodes.push(position);
+
+        let (x, y) = (position.x as usize, position.y as usize);
+        if self.search_grid[x][y] > self.rhs_grid[x][y] {
+            // Overconsistent, the cell got cheaper
+            self.search_grid[x][y] = self.rhs_grid[x][y];
+        } else {
+            // Underconsistent, the cell got more expensive and its neighbours may have depended on it
+            self.search_grid[x][y] = f32::MAX;
+            self.update_cell(&position);
+        }
+        for cell in self.maze.neighbours(&position).collect::<Vec<_>>() {
+            self.update_cell(&cell);
+        }
+
+        false
+    }
+
+    fn finish(&mut self) {
+        let target = self.target();
+        if self.g(&target) == f32::MAX {
+            self.outcome = SearchOutcome::Unreachable;
+            return;
+        }
+
+        self.outcome = SearchOutcome::Found;
+
+        // Follow the cheapest neighbours back to the root, costs are positive so g strictly decreases
+        let root = self.root();
+        let mut position = target;
+        self.complete_path.push(position);
+        while position != root {
+            position = self
+                .maze
+                .neighbours(&position)
+                .min_by(|a, b| {
+                    let cost_a = self.g(a) + self.edge_cost(a, &position);
+                    let cost_b = self.g(b) + self.edge_cost(b, &position);
+                    cost_a.total_cmp(&cost_b)
+                })
+                .unwrap();
+            self.complete_path.push(position);
+        }
+
+        if !self.backward {
+            self.complete_path.reverse();
+        }
+    }
+
+    /// Recompute the rhs value of a cell and queue it if it became inconsistent
+    fn update_cell(&mut self, position: &GridPosition) {
+        if *position != self.root() {
+            let rhs = if self.maze.is_cell_valid(position) {
+                self.maze
+                    .neighbours(position)
+                    .map(|cell| self.g(&cell) + self.edge_cost(&cell, position))
+                    .fold(f32::MAX, f32::min)
+            } else {
+                f32::MAX
+            };
+            self.rhs_grid[position.x as usize][position.y as usize] = rhs;
+        }
+
+        if !self.is_consistent(position) {
+            self.push(*position);
+        }
+    }
+
+    /// Change the value of a cell and queue the cells whose cheapest neighbour may have changed,
+    /// which for 8-connected mazes includes the diagonal moves squeezing past the cell
+    pub(crate) fn set_cell(&mut self, position: &GridPosition, value: u8) {
+        let cell = &mut self.maze.grid[position.x as usize][position.y as usize];
+        if *cell == value {
+            return;
+        }
+        *cell = value;
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let neighbour = GridPosition::new(position.x + dx, position.y + dy);
+                if neighbour.x >= 0
+                    && neighbour.y >= 0
+                    && (neighbour.x as usize) < self.maze.grid.len()
+                    && (neighbour.y as usize) < self.maze.grid[0].len()
+                {
+                    self.update_cell(&neighbour);
+                }
+            }
+        }
+
+        log::info!(
+            "{} repairing its search after cell ({},{}) changed",
+            self.name,
+            position.x,
+            position.y
+        );
+        self.restart_repair();
+    }
+
+    /// Move the start of a backward search, the cells expanded so far remain valid
+    pub(crate) fn set_start(&mut self, position: &GridPosition) {
+        debug_assert!(self.backward);
+
+        self.km += self.heuristic.distance(&self.position_start, position);
+        self.position_start = *position;
+
+        log::info!(
+            "{} repairing its search after the start moved to ({},{})",
+            self.name,
+            position.x,
+            position.y
+        );
+        self.restart_repair();
+    }
+
+    fn restart_repair(&mut self) {
+        self.outcome = SearchOutcome::InProgress;
+        self.complete_path.clear();
+        self.repair_expanded_nodes.clear();
+    }
+
+    fn push(&mut self, position: GridPosition) {
+        let key = self.key(&position);
+        self.current_search_positions
+            .push(Reverse(QueueEntry { key, position }));
+    }
+
+    fn key(&self, position: &GridPosition) -> (f32, f32) {
+        let cost = self.g(position).min(self.rhs(position));
+        (cost + self.h(position) + self.km, cost)
+    }
+
+    fn is_consistent(&self, position: &GridPosition) -> bool {
+        self.g(position) == self.rhs(position)
+    }
+
+    /// Cell the search grows from
+    fn root(&self) -> GridPosition {
+        if self.backward {
+            self.position_end
+        } else {
+            self.position_start
+        }
+    }
+
+    /// Cell the search is looking for
+    fn target(&self) -> GridPosition {
+        if self.backward {
+            self.position_start
+        } else {
+            self.position_end
+        }
+    }
+
+    /// Cost of extending the search from `from` to the neighbouring `to`, backward searches walk moves in reverse
+    fn edge_cost(&self, from: &GridPosition, to: &GridPosition) -> f32 {
+        if self.backward {
+            self.maze.move_cost(to, from)
+        } else {
+            self.maze.move_cost(from, to)
+        }
+    }
+
+    fn g(&self, position: &GridPosition) -> f32 {
+        self.search_grid[position.x as usize][position.y as usize]
+    }
+
+    fn rhs(&self, position: &GridPosition) -> f32 {
+        self.rhs_grid[position.x as usize][position.y as usize]
+    }
+
+    /// Heuristic function - distance from the position parameter to the target
+    fn h(&self, position: &GridPosition) -> f32 {
+        self.heuristic.distance(position, &self.target())
+    }
+
+    pub(crate) fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    pub(crate) fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    pub(crate) fn abort(&mut self) {
+        self.outcome = SearchOutcome::Aborted;
+    }
+
+    pub(crate) fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    /// Inconsistent cells waiting in the queue
+    pub(crate) fn frontier(&self) -> Vec<GridPosition> {
+        let mut frontier = self
+            .current_search_positions
+            .iter()
+            .map(|entry| entry.0.position)
+            .filter(|position| !self.is_consistent(position))
+            .collect::<Vec<_>>();
+        frontier.sort_by_key(|position| (position.x, position.y));
+        frontier.dedup();
+        frontier
+    }
+
+    /// g is the smaller of g and rhs, f the primary queue key
+    pub(crate) fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        let (f, g) = self.key(position);
+        (g != f32::MAX).then(|| CellValues {
+            g,
+            h: self.h(position),
+            f,
+        })
+    }
+
+    pub(crate) fn repair_expanded_nodes(&self) -> &[GridPosition] {
+        &self.repair_expanded_nodes
+    }
+
+    pub(crate) fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    pub(crate) fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    pub(crate) fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+/// Lifelong Planning A*, A* that repairs its search when cells change instead of starting over
+pub struct MazeSolverLPAStar {
+    search: IncrementalSearch,
+}
+
+impl MazeSolverLPAStar {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        Self {
+            search: IncrementalSearch::new("LPA*", maze, position_start, position_end, false),
+        }
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        if !heuristic.is_admissible(self.search.maze().connectivity) {
+            log::warn!(
+                "{:?} heuristic is not admissible with {:?} movement, LPA* may not find the cheapest path",
+                heuristic,
+                self.search.maze().connectivity
+            );
+        }
+        self.search.set_heuristic(heuristic);
+        self
+    }
+
+    fn search(&mut self) {
+        if self.search.search() {
+            print_result_path(self);
+        }
+    }
+}
+
+impl MazeSolver for MazeSolverLPAStar {
+    fn name(&self) -> &'static str {
+        "LPA*"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.search.outcome() == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        self.search.expanded_nodes()
+    }
+
+    fn start(&self) -> &GridPosition {
+        self.search.start()
+    }
+
+    fn end(&self) -> &GridPosition {
+        self.search.end()
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.search.outcome()
+    }
+
+    fn abort(&mut self) {
+        if self.search.outcome() == SearchOutcome::InProgress {
+            self.search.abort();
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.search.maze().path_cost(self.search.path())
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        self.search.path()
+    }
+
+    fn set_cell(&mut self, position: &GridPosition, value: u8) -> bool {
+        self.search.set_cell(position, value);
+        true
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverLPAStar {
+    fn update(&mut self) {
+        if self.search.outcome() == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn drawn_expanded_nodes(&self) -> &[GridPosition] {
+        self.search.repair_expanded_nodes()
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.search.frontier()
+    }
+
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        self.search.cell_values(position)
+    }
+}
@@ -1,204 +1,275 @@
-use ggez::{
-    event::{self, EventHandler},
-    graphics::{Canvas, Color},
-    input::keyboard::{KeyCode, KeyInput},
-    Context as GgezContext, GameResult,
+use maze_solver::{
+    generator::*, mapf::*, maze::*, multi_goal::*, parser::format_maze, report::*, solver::*,
 };
 
-use crate::{astar::*, bfs::*, dfs::*, maze::*, renderer::*};
+use crate::cli::*;
 
-mod astar;
-mod bfs;
-mod dfs;
-mod maze;
-mod renderer;
+mod cli;
 
-const TARGET_FPS: u32 = 30;
+fn main() {
+    env_logger::Builder::from_default_env()
+        .filter_module(env!("CARGO_PKG_NAME"), log::LevelFilter::Trace)
+        .init();
 
-enum MazeSolveAlgorithm {
-    BFS,
-    DFS,
-    ASTAR,
-}
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = match Command::parse(&args) {
+        Ok(Command::Solve(args)) => args,
+        Ok(Command::Generate(args)) => {
+            generate(&args);
+            return;
+        }
+        Ok(Command::Mapf(args)) => {
+            run_mapf(&args);
+            return;
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            log::error!("{}", USAGE);
+            std::process::exit(1)
+        }
+    };
 
-struct GameState {
-    maze: Maze,
-    position_start: GridPosition,
-    position_end: GridPosition,
+    let maze = load_maze(&args.maze_file_name, args.connectivity);
 
-    solver_bfs: MazeSolverBFS,
-    solver_dfs: MazeSolverDFS,
-    solver_astar: MazeSolverAStar,
+    let (position_start, goals) = resolve_endpoints(&args, &maze).unwrap_or_else(|e| {
+        log::error!("{}", e);
+        std::process::exit(1)
+    });
 
-    current_algorithm: MazeSolveAlgorithm,
+    if args.headless {
+        run_headless(&args, maze, position_start, goals);
+    } else {
+        run_viewer(&args, maze, position_start, goals);
+    }
+}
 
-    renderer: Renderer,
+fn load_maze(maze_file_name: &str, connectivity: Connectivity) -> Maze {
+    let maze_string = std::fs::read_to_string(maze_file_name).unwrap_or_else(|_| {
+        log::error!("Failed to read maze file: {}", maze_file_name);
+        std::process::exit(1)
+    });
 
-    path_trace: bool,
-    path_position_index: usize,
-    path_traced: Vec<GridPosition>,
+    let mut maze = Maze::new_from_string(&maze_string).unwrap_or_else(|e| {
+        log::error!("Failed to parse maze file {}: {}", maze_file_name, e);
+        std::process::exit(1)
+    });
+
+    maze.connectivity = connectivity;
+    maze
 }
 
-impl GameState {
-    /// Reset to re-draw start -> finish path
-    fn reset_path_rendering(&mut self) {
-        self.path_trace = false;
-        // Do not draw path over the starting node
-        self.path_position_index = 1;
-        self.path_traced.clear();
+fn generate(args: &GenerateArgs) {
+    // Seeded generation is deterministic, so the written maze matches the animated one
+    if args.view {
+        if let Some(output_file_name) = args.output_file_name.as_deref() {
+            let maze = MazeGenerator::new(args.algorithm, &args.options).generate();
+            write_output(Some(output_file_name), &format_maze(&maze));
+        }
+        run_generation_viewer(args);
+        return;
     }
-}
 
-impl EventHandler for GameState {
-    fn update(&mut self, ctx: &mut GgezContext) -> GameResult {
-        while ctx.time.check_update_time(TARGET_FPS) {
-            let solver: &mut dyn MazeSolverDrawable;
-            match self.current_algorithm {
-                MazeSolveAlgorithm::BFS => solver = &mut self.solver_bfs,
-                MazeSolveAlgorithm::DFS => solver = &mut self.solver_dfs,
-                MazeSolveAlgorithm::ASTAR => solver = &mut self.solver_astar,
-            }
+    log::info!(
+        "Generating {}x{} cell maze with {} algorithm, seed {}",
+        args.options.rows,
+        args.options.columns,
+        args.algorithm.name(),
+        args.options.seed
+    );
 
-            solver.update();
+    let maze = MazeGenerator::new(args.algorithm, &args.options).generate();
+    write_output(args.output_file_name.as_deref(), &format_maze(&maze));
+}
 
-            // Update path tracing
-            if self.path_trace {
-                if solver.is_complete() && self.path_position_index < solver.path().len() {
-                    self.path_traced
-                        .push(solver.path()[self.path_position_index]);
-                    self.path_position_index += 1;
-                }
-            }
-        }
+/// Write to a file, or stdout if no file name is given, ending with a single newline either way
+fn write_output(output_file_name: Option<&str>, output: &str) {
+    let output = output.trim_end_matches('\n');
+    match output_file_name {
+        Some(output_file_name) => std::fs::write(output_file_name, format!("{}\n", output))
+            .unwrap_or_else(|_| {
+                log::error!("Failed to write output file: {}", output_file_name);
+                std::process::exit(1)
+            }),
+        None => println!("{}", output),
+    }
+}
+
+/// Start and goals from the command line, falling back to the positions marked in the maze file
+fn resolve_endpoints(
+    args: &Args,
+    maze: &Maze,
+) -> anyhow::Result<(GridPosition, Vec<GridPosition>)> {
+    let position_start = args.position_start.or(maze.position_start).ok_or_else(|| {
+        anyhow::anyhow!("No start position, pass --start x,y or mark a cell with S")
+    })?;
+    let goals = if args.goals.is_empty() {
+        vec![maze.position_end.ok_or_else(|| {
+            anyhow::anyhow!("No goal position, pass --goal x,y or mark a cell with G")
+        })?]
+    } else {
+        args.goals.clone()
+    };
 
-        Ok(())
+    maze.validate_position(&position_start, "Start")?;
+    for goal in &goals {
+        maze.validate_position(goal, "Goal")?;
     }
 
-    fn draw(&mut self, ctx: &mut GgezContext) -> GameResult {
-        let mut canvas = Canvas::from_frame(ctx, Color::WHITE);
+    Ok((position_start, goals))
+}
 
-        // Draw base maze
-        self.renderer.draw_maze(&mut canvas, &self.maze);
+fn run_headless(args: &Args, maze: Maze, position_start: GridPosition, goals: Vec<GridPosition>) {
+    if !args.compare.is_empty() {
+        log::warn!(
+            "--compare only applies to the viewer, use --algorithms to select headless solvers"
+        );
+    }
 
-        let solver: &mut dyn MazeSolverDrawable;
-        match self.current_algorithm {
-            MazeSolveAlgorithm::BFS => solver = &mut self.solver_bfs,
-            MazeSolveAlgorithm::DFS => solver = &mut self.solver_dfs,
-            MazeSolveAlgorithm::ASTAR => solver = &mut self.solver_astar,
-        }
+    let solvers: Vec<Box<dyn MazeSolverDrawable>> = if goals.len() == 1 {
+        args.algorithms
+            .iter()
+            .map(|algorithm| {
+                algorithm.new_solver(maze.clone(), position_start, goals[0], &args.options)
+            })
+            .collect()
+    } else if args.goal_mode == GoalMode::All {
+        // The visiting order does not depend on the selected algorithms
+        vec![Box::new(MazeSolverAllGoals::new(
+            maze,
+            position_start,
+            goals,
+        ))]
+    } else {
+        args.algorithms
+            .iter()
+            .filter_map(|algorithm| {
+                let solver = algorithm.new_nearest_goal_solver(
+                    maze.clone(),
+                    position_start,
+                    goals.clone(),
+                    &args.options,
+                );
+                if solver.is_none() {
+                    log::warn!("{:?} only searches for a single goal, skipped", algorithm);
+                }
+                solver
+            })
+            .collect()
+    };
 
-        self.renderer.draw_maze_solver(&mut canvas, solver);
+    let reports = solvers
+        .into_iter()
+        .map(|mut solver| run_with_report(solver.as_mut(), args.max_expansions))
+        .collect::<Vec<_>>();
 
-        // Trace path
-        for cell in &self.path_traced {
-            self.renderer
-                .draw_cell(&mut canvas, cell, MazeColor::PATH_TRACE.0);
-        }
+    let output = format_reports(&reports, args.format);
+    write_output(args.output_file_name.as_deref(), &output);
+}
 
-        canvas.finish(ctx)?;
+fn run_mapf(args: &MapfArgs) {
+    let maze = load_maze(&args.maze_file_name, args.connectivity);
 
-        ggez::timer::yield_now();
+    let agents = resolve_agents(args, &maze).unwrap_or_else(|e| {
+        log::error!("{}", e);
+        std::process::exit(1)
+    });
 
-        Ok(())
+    if !args.headless {
+        run_mapf_viewer(args, maze, agents);
+        return;
     }
 
-    fn key_down_event(
-        &mut self,
-        _ctx: &mut GgezContext,
-        input: KeyInput,
-        _repeat: bool,
-    ) -> GameResult {
-        match input.keycode {
-            Some(KeyCode::B) => {
-                self.current_algorithm = MazeSolveAlgorithm::BFS;
-                // XXX: Add reset functionality
-                self.solver_bfs =
-                    MazeSolverBFS::new(self.maze.clone(), self.position_start, self.position_end);
-                self.reset_path_rendering();
-            }
-            Some(KeyCode::D) => {
-                self.current_algorithm = MazeSolveAlgorithm::DFS;
-                // XXX: Add reset functionality
-                self.solver_dfs =
-                    MazeSolverDFS::new(self.maze.clone(), self.position_start, self.position_end);
-                self.reset_path_rendering();
-            }
-            Some(KeyCode::A) => {
-                self.current_algorithm = MazeSolveAlgorithm::ASTAR;
-                // XXX: Add reset functionality
-                self.solver_astar =
-                    MazeSolverAStar::new(self.maze.clone(), self.position_start, self.position_end);
-                self.reset_path_rendering();
-            }
-            Some(KeyCode::T) => {
-                self.reset_path_rendering();
-                self.path_trace = true;
+    let reports = args
+        .algorithms
+        .iter()
+        .map(|&algorithm| {
+            let mut solver = MapfSolver::new(algorithm, maze.clone(), agents.clone())
+                .with_max_nodes(args.max_nodes);
+            run_mapf_with_report(&mut solver)
+        })
+        .collect::<Vec<_>>();
+
+    let output = format_mapf_reports(&reports, args.format);
+    write_output(args.output_file_name.as_deref(), &output);
+}
+
+/// Agents from the command line, random ones, or a single agent between the start and goal marked in the maze file
+fn resolve_agents(args: &MapfArgs, maze: &Maze) -> anyhow::Result<Vec<Agent>> {
+    let agents = if !args.agents.is_empty() {
+        args.agents.clone()
+    } else if args.random_agents > 0 {
+        log::info!(
+            "Placing {} random agents, seed {}",
+            args.random_agents,
+            args.seed
+        );
+        random_agents(maze, args.random_agents, args.seed)?
+    } else {
+        match (maze.position_start, maze.position_end) {
+            (Some(start), Some(goal)) => vec![Agent::new(start, goal)],
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "No agents, pass --agent x,y:x,y or --agents n, or mark cells with S and G"
+                ))
             }
-            _ => {}
         }
+    };
 
-        Ok(())
-    }
+    validate_agents(maze, &agents)?;
+    Ok(agents)
 }
 
-fn main() {
-    env_logger::Builder::from_default_env()
-        .filter_module(env!("CARGO_PKG_NAME"), log::LevelFilter::Trace)
-        .init();
-
-    let args = std::env::args().collect::<Vec<_>>();
-    if args.len() < 2 {
-        log::error!("Usage: maze_solver [maze_file]");
-        std::process::exit(1);
-    }
-
-    let maze_file_name = args[1].as_str();
-    let maze_string = std::fs::read_to_string(maze_file_name).unwrap_or_else(|_| {
-        log::error!("Failed to read maze file: {}", maze_file_name);
-        std::process::exit(1)
-    });
-
-    let position_start = GridPosition::new(1, 3);
-    let position_end = GridPosition::new(17, 14);
-
-    let maze = Maze::new_from_string(
-        &maze_string
-            .trim()
-            .strip_prefix('[')
-            .and_then(|s| s.strip_suffix(']'))
-            .unwrap_or(&maze_string),
+#[cfg(feature = "gui")]
+fn run_viewer(args: &Args, maze: Maze, position_start: GridPosition, goals: Vec<GridPosition>) {
+    maze_solver::viewer::run(
+        maze,
+        position_start,
+        goals,
+        args.goal_mode,
+        args.options,
+        args.compare.clone(),
     )
-    .unwrap_or_else(|_| {
-        log::error!("Failed to parse maze string: \n{}", maze_string);
+    .unwrap_or_else(|e| {
+        log::error!("Viewer failed: {}", e);
         std::process::exit(1)
     });
+}
 
-    let (ctx, events_loop) = ggez::ContextBuilder::new("MazeSolver", "fairywreath")
-        .window_setup(ggez::conf::WindowSetup::default().title("Maze Solver"))
-        .window_mode(ggez::conf::WindowMode::default().dimensions(SCREEN_WIDTH, SCREEN_HEIGHT))
-        .build()
-        .unwrap();
+#[cfg(feature = "gui")]
+fn run_generation_viewer(args: &GenerateArgs) {
+    maze_solver::viewer::run_generation(args.algorithm, args.options, Default::default())
+        .unwrap_or_else(|e| {
+            log::error!("Viewer failed: {}", e);
+            std::process::exit(1)
+        });
+}
 
-    let solver_bfs = MazeSolverBFS::new(maze.clone(), position_start, position_end);
-    let solver_dfs = MazeSolverDFS::new(maze.clone(), position_start, position_end);
-    let solver_astar = MazeSolverAStar::new(maze.clone(), position_start, position_end);
+#[cfg(feature = "gui")]
+fn run_mapf_viewer(args: &MapfArgs, maze: Maze, agents: Vec<Agent>) {
+    maze_solver::viewer::run_mapf(maze, agents, args.algorithms[0], args.max_nodes).unwrap_or_else(
+        |e| {
+            log::error!("Viewer failed: {}", e);
+            std::process::exit(1)
+        },
+    );
+}
 
-    let renderer = Renderer::new_with_maze(&maze);
+#[cfg(not(feature = "gui"))]
+fn run_mapf_viewer(_args: &MapfArgs, _maze: Maze, _agents: Vec<Agent>) {
+    log::error!("maze_solver was built without the viewer, rebuild with `--features gui` or pass --headless");
+    std::process::exit(1);
+}
 
-    let state = GameState {
-        maze,
-        position_start,
-        position_end,
-        solver_bfs,
-        solver_dfs,
-        solver_astar,
-        current_algorithm: MazeSolveAlgorithm::ASTAR,
-        renderer,
-
-        path_trace: false,
-        path_position_index: 0,
-        path_traced: Vec::new(),
-    };
+#[cfg(not(feature = "gui"))]
+fn run_generation_viewer(_args: &GenerateArgs) {
+    log::error!(
+        "maze_solver was built without the viewer, rebuild with `--features gui` to use --view"
+    );
+    std::process::exit(1);
+}
 
-    event::run(ctx, events_loop, state)
+#[cfg(not(feature = "gui"))]
+fn run_viewer(_args: &Args, _maze: Maze, _position_start: GridPosition, _goals: Vec<GridPosition>) {
+    log::error!("maze_solver was built without the viewer, rebuild with `--features gui` or pass --headless");
+    std::process::exit(1);
 }
@@ -0,0 +1,676 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use anyhow::{anyhow, Result};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::Serialize;
+
+use crate::{maze::*, solver::SearchOutcome};
+
+/// Constraint tree nodes CBS expands before giving up, the tree can grow exponentially with the number of conflicts
+pub const DEFAULT_MAX_CBS_NODES: usize = 10_000;
+
+/// Agent routed from its start to its goal, where it stays once it arrived
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct Agent {
+    pub start: GridPosition,
+    pub goal: GridPosition,
+}
+
+impl Agent {
+    pub fn new(start: GridPosition, goal: GridPosition) -> Self {
+        Self { start, goal }
+    }
+}
+
+impl std::str::FromStr for Agent {
+    type Err = anyhow::Error;
+
+    /// Parse "x,y:x,y" as start and goal
+    fn from_str(s: &str) -> Result<Self> {
+        let (start, goal) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid agent \"{}\", expected x,y:x,y", s))?;
+        Ok(Self::new(start.parse()?, goal.parse()?))
+    }
+}
+
+/// Pick agents with distinct random starts and goals, each goal reachable from its start.
+/// Cells from which no other free cell is reachable are never picked.
+pub fn random_agents(maze: &Maze, count: usize, seed: u64) -> Result<Vec<Agent>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut cells = (0..maze.grid.len())
+        .flat_map(|x| (0..maze.grid[0].len()).map(move |y| GridPosition::new(x as _, y as _)))
+        .filter(|position| maze.is_cell_valid(position))
+        .collect::<Vec<_>>();
+    cells.shuffle(&mut rng);
+    let mut goal_cells = cells.clone();
+    goal_cells.shuffle(&mut rng);
+
+    let mut agents = Vec::with_capacity(count);
+    let mut goals = HashSet::new();
+    for &start in &cells {
+        if agents.len() == count {
+            break;
+        }
+
+        let distances = distance_grid(maze, &start);
+        let goal = goal_cells.iter().find(|&&goal| {
+            goal != start
+                && distances[goal.x as usize][goal.y as usize] != u32::MAX
+                && !goals.contains(&goal)
+        });
+        if let Some(&goal) = goal {
+            goals.insert(goal);
+            agents.push(Agent::new(start, goal));
+        }
+    }
+
+    if agents.len() < count {
+        return Err(anyhow!(
+            "Maze only has room for {} of {} agents",
+            agents.len(),
+            count
+        ));
+    }
+    Ok(agents)
+}
+
+/// Check that agents start and end on free cells, none sharing a start or goal
+pub fn validate_agents(maze: &Maze, agents: &[Agent]) -> Result<()> {
+    if agents.is_empty() {
+        return Err(anyhow!("No agents"));
+    }
+    for (index, agent) in agents.iter().enumerate() {
+        maze.validate_position(&agent.start, &format!("Agent {} start", index))?;
+        maze.validate_position(&agent.goal, &format!("Agent {} goal", index))?;
+        if agents[..index]
+            .iter()
+            .any(|other| other.start == agent.start)
+        {
+            return Err(anyhow!(
+                "Agent {} starts on the same cell as another agent",
+                index
+            ));
+        }
+        if agents[..index].iter().any(|other| other.goal == agent.goal) {
+            return Err(anyhow!(
+                "Agent {} has the same goal as another agent",
+                index
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Number of moves from every cell to `target`, u32::MAX for cells that cannot reach it
+fn distance_grid(maze: &Maze, target: &GridPosition) -> Vec<Vec<u32>> {
+    let mut distances = vec![vec![u32::MAX; maze.grid[0].len()]; maze.grid.len()];
+    distances[target.x as usize][target.y as usize] = 0;
+
+    // Moves are symmetric, so searching from the target gives the distances towards it
+    let mut queue = VecDeque::from([*target]);
+    while let Some(position) = queue.pop_front() {
+        let distance = distances[position.x as usize][position.y as usize];
+        for cell in maze.neighbours(&position) {
+            if distances[cell.x as usize][cell.y as usize] == u32::MAX {
+                distances[cell.x as usize][cell.y as usize] = distance + 1;
+                queue.push_back(cell);
+            }
+        }
+    }
+
+    distances
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MapfAlgorithm {
+    /// Plan agents one after another around the reservations of the earlier ones
+    CooperativeAStar,
+    /// Resolve conflicts between independently planned paths by branching on constraints, optimal sum of costs
+    ConflictBasedSearch,
+}
+
+impl MapfAlgorithm {
+    pub const ALL: [Self; 2] = [Self::CooperativeAStar, Self::ConflictBasedSearch];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CooperativeAStar => "Cooperative A*",
+            Self::ConflictBasedSearch => "CBS",
+        }
+    }
+}
+
+impl std::str::FromStr for MapfAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ca" | "ca*" | "cooperative-astar" => Ok(Self::CooperativeAStar),
+            "cbs" | "conflict-based-search" => Ok(Self::ConflictBasedSearch),
+            _ => Err(anyhow!("Unknown MAPF algorithm: {}", s)),
+        }
+    }
+}
+
+/// Space-time reservations: which cells and moves are taken at which time step
+#[derive(Clone, Default)]
+pub struct ReservationTable {
+    cells: HashSet<(GridPosition, u32)>,
+    /// Moves arriving at the time step
+    moves: HashSet<(GridPosition, GridPosition, u32)>,
+    /// Cells taken from a time step on by agents resting at their goal
+    parked: HashMap<GridPosition, u32>,
+    /// Last time step each cell is reserved at, a cell can only be rested on after it
+    latest: HashMap<GridPosition, u32>,
+}
+
+impl ReservationTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reserve_cell(&mut self, position: GridPosition, time: u32) {
+        self.cells.insert((position, time));
+        let latest = self.latest.entry(position).or_insert(time);
+        *latest = (*latest).max(time);
+    }
+
+    /// Forbid moving from `from` to `to` arriving at `time`
+    pub fn reserve_move(&mut self, from: GridPosition, to: GridPosition, time: u32) {
+        self.moves.insert((from, to, time));
+    }
+
+    /// Reserve the cells of a timed path, the opposite moves so no agent swaps places with it,
+    /// and its last cell for good
+    pub fn reserve_path(&mut self, path: &[GridPosition]) {
+        for (time, &position) in path.iter().enumerate() {
+            self.reserve_cell(position, time as _);
+        }
+        for (time, step) in path.windows(2).enumerate() {
+            self.reserve_move(step[1], step[0], time as u32 + 1);
+        }
+        if let Some(&last) = path.last() {
+            self.parked.insert(last, path.len() as u32 - 1);
+        }
+    }
+
+    pub fn is_cell_free(&self, position: &GridPosition, time: u32) -> bool {
+        !self.cells.contains(&(*position, time))
+            && self.parked.get(position).is_none_or(|&since| time < since)
+    }
+
+    pub fn is_move_free(&self, from: &GridPosition, to: &GridPosition, time: u32) -> bool {
+        !self.moves.contains(&(*from, *to, time))
+    }
+
+    /// Whether an agent can stay on the cell for good from `time` on
+    pub fn can_rest(&self, position: &GridPosition, time: u32) -> bool {
+        !self.parked.contains_key(position)
+            && self
+                .latest
+                .get(position)
+                .is_none_or(|&latest| latest < time)
+    }
+
+    /// Last time step with a reservation, after which only parked agents remain
+    fn horizon(&self) -> u32 {
+        self.latest
+            .values()
+            .chain(self.parked.values())
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// A* over (cell, time step) states avoiding the reserved cells and moves, waiting in place costs a time step like
+/// moving. Returns the timed path ending once the agent can rest at its goal.
+fn plan_path(
+    maze: &Maze,
+    agent: &Agent,
+    reservations: &ReservationTable,
+    distances: &[Vec<u32>],
+    expanded_nodes: &mut usize,
+) -> Option<Vec<GridPosition>> {
+    let h = |position: &GridPosition| distances[position.x as usize][position.y as usize];
+    if h(&agent.start) == u32::MAX || !reservations.is_cell_free(&agent.start, 0) {
+        return None;
+    }
+
+    // Waiting longer than visiting every cell after the last reservation never helps
+    let free_cells = maze
+        .grid
+        .iter()
+        .flatten()
+        .filter(|&&cell| cell != CELL_WALL)
+        .count() as u32;
+    let max_time = reservations.horizon() + free_cells;
+
+    let mut open = BinaryHeap::new();
+    let mut closed = HashSet::new();
+    let mut parents = HashMap::new();
+    // f = time + h, ties prefer states closer to the goal
+    open.push(Reverse((
+        h(&agent.start),
+        h(&agent.start),
+        0u32,
+        agent.start.x,
+        agent.start.y,
+    )));
+
+    while let Some(Reverse((_, _, time, x, y))) = open.pop() {
+        let position = GridPosition::new(x, y);
+        if !closed.insert((position, time)) {
+            continue;
+        }
+        *expanded_nodes += 1;
+
+        if position == agent.goal && reservations.can_rest(&position, time) {
+            let mut path = vec![position];
+            let mut state = (position, time);
+            while let Some(&parent) = parents.get(&state) {
+                path.push(parent);
+                state = (parent, state.1 - 1);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if time == max_time {
+            continue;
+        }
+
+        let next_time = time + 1;
+        for cell in std::iter::once(position).chain(maze.neighbours(&position)) {
+            if closed.contains(&(cell, next_time))
+                || !reservations.is_cell_free(&cell, next_time)
+                || !reservations.is_move_free(&position, &cell, next_time)
+            {
+                continue;
+            }
+
+            // Every path to a state takes the same time, so its first parent is as good as any
+            if let Entry::Vacant(entry) = parents.entry((cell, next_time)) {
+                entry.insert(position);
+                open.push(Reverse((
+                    next_time + h(&cell),
+                    h(&cell),
+                    next_time,
+                    cell.x,
+                    cell.y,
+                )));
+            }
+        }
+    }
+
+    None
+}
+
+/// Cell of a timed path at any time step, agents stay at their last cell
+fn position_at(path: &[GridPosition], time: usize) -> GridPosition {
+    path[time.min(path.len() - 1)]
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Conflict {
+    /// Two agents in the same cell at the same time
+    Cell(usize, usize, GridPosition, u32),
+    /// Two agents swapping cells, arriving at the time step
+    Swap(usize, usize, u32),
+}
+
+impl Conflict {
+    fn time(&self) -> u32 {
+        match *self {
+            Self::Cell(_, _, _, time) | Self::Swap(_, _, time) => time,
+        }
+    }
+}
+
+/// First conflict between the paths of agents `a` and `b`
+fn pair_conflict(paths: &[Vec<GridPosition>], a: usize, b: usize) -> Option<Conflict> {
+    let makespan = paths[a].len().max(paths[b].len());
+    for time in 0..makespan {
+        let position = position_at(&paths[a], time);
+        if position == position_at(&paths[b], time) {
+            return Some(Conflict::Cell(a, b, position, time as _));
+        }
+        if time > 0
+            && position == position_at(&paths[b], time - 1)
+            && position_at(&paths[a], time - 1) == position_at(&paths[b], time)
+        {
+            return Some(Conflict::Swap(a, b, time as _));
+        }
+    }
+    None
+}
+
+fn agent_pairs(agents: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..agents).flat_map(move |a| (a + 1..agents).map(move |b| (a, b)))
+}
+
+/// Earliest conflict between any two paths
+fn find_conflict(paths: &[Vec<GridPosition>]) -> Option<Conflict> {
+    agent_pairs(paths.len())
+        .filter_map(|(a, b)| pair_conflict(paths, a, b))
+        .min_by_key(Conflict::time)
+}
+
+/// Number of conflicting agent pairs, CBS prefers nodes with fewer conflicts among equally cheap ones
+fn count_conflicts(paths: &[Vec<GridPosition>]) -> usize {
+    agent_pairs(paths.len())
+        .filter(|&(a, b)| pair_conflict(paths, a, b).is_some())
+        .count()
+}
+
+fn sum_of_costs(paths: &[Vec<GridPosition>]) -> usize {
+    paths.iter().map(|path| path.len() - 1).sum()
+}
+
+/// CBS constraint tree node
+struct ConstraintNode {
+    /// Reservations each agent has to avoid
+    constraints: Vec<ReservationTable>,
+    paths: Vec<Vec<GridPosition>>,
+}
+
+/// Collision-free timed paths for several agents sharing the maze. Every move or wait takes 1 time step and agents
+/// may neither share a cell nor swap places. Terrain costs are ignored.
+pub struct MapfSolver {
+    algorithm: MapfAlgorithm,
+    maze: Maze,
+    agents: Vec<Agent>,
+
+    /// Moves from every cell to each agent's goal ignoring the other agents, the low-level heuristic
+    distances: Vec<Vec<Vec<u32>>>,
+
+    /// Cooperative A*: reservations of the agents planned so far
+    reservations: ReservationTable,
+
+    /// CBS: constraint tree and the open nodes ordered by sum of costs and conflicts
+    constraint_tree: Vec<ConstraintNode>,
+    open_nodes: BinaryHeap<Reverse<(usize, usize, usize)>>,
+    max_nodes: usize,
+    high_level_nodes: usize,
+
+    outcome: SearchOutcome,
+    paths: Vec<Vec<GridPosition>>,
+
+    /// Low-level (cell, time step) expansions
+    expanded_nodes: usize,
+}
+
+impl MapfSolver {
+    pub fn new(algorithm: MapfAlgorithm, maze: Maze, agents: Vec<Agent>) -> Self {
+        if maze.max_cell_cost() > 1.0 {
+            log::warn!("Multi-agent pathfinding treats terrain as empty cells");
+        }
+
+        let distances = agents
+            .iter()
+            .map(|agent| distance_grid(&maze, &agent.goal))
+            .collect();
+
+        Self {
+            algorithm,
+            maze,
+            agents,
+            distances,
+
+            reservations: ReservationTable::new(),
+
+            constraint_tree: Vec::new(),
+            open_nodes: BinaryHeap::new(),
+            max_nodes: DEFAULT_MAX_CBS_NODES,
+            high_level_nodes: 0,
+
+            outcome: SearchOutcome::InProgress,
+            paths: Vec::new(),
+
+            expanded_nodes: 0,
+        }
+    }
+
+    /// Constraint tree nodes CBS may expand before aborting
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// 1 step of search: planning 1 agent for Cooperative A*, expanding 1 constraint tree node for CBS.
+    /// Returns false once the search is no longer in progress.
+    pub fn step(&mut self) -> bool {
+        if self.outcome == SearchOutcome::InProgress {
+            match self.algorithm {
+                MapfAlgorithm::CooperativeAStar => self.cooperative_step(),
+                MapfAlgorithm::ConflictBasedSearch => self.cbs_step(),
+            }
+        }
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+
+    /// Plan the next agent around the paths of the earlier ones
+    fn cooperative_step(&mut self) {
+        let index = self.paths.len();
+        if index == self.agents.len() {
+            self.finish(SearchOutcome::Found);
+            return;
+        }
+
+        match plan_path(
+            &self.maze,
+            &self.agents[index],
+            &self.reservations,
+            &self.distances[index],
+            &mut self.expanded_nodes,
+        ) {
+            Some(path) => {
+                self.reservations.reserve_path(&path);
+                self.paths.push(path);
+            }
+            None => {
+                // Earlier agents never make way, so this does not prove that no solution exists
+                log::info!(
+                    "Cooperative A* found no path for agent {} around the {} agents planned before it, aborting",
+                    index,
+                    index
+                );
+                self.paths.clear();
+                self.finish(SearchOutcome::Aborted);
+            }
+        }
+    }
+
+    /// Expand the cheapest constraint tree node, or create the root on the first step
+    fn cbs_step(&mut self) {
+        if self.constraint_tree.is_empty() {
+            let constraints = vec![ReservationTable::new(); self.agents.len()];
+            let Some(paths) = (0..self.agents.len())
+                .map(|agent| self.plan_agent(agent, &constraints[agent]))
+                .collect::<Option<Vec<_>>>()
+            else {
+                self.finish(SearchOutcome::Unreachable);
+                return;
+            };
+            self.push_node(ConstraintNode { constraints, paths });
+            return;
+        }
+
+        let Some(Reverse((_, _, index))) = self.open_nodes.pop() else {
+            self.finish(SearchOutcome::Unreachable);
+            return;
+        };
+        if self.high_level_nodes == self.max_nodes {
+            log::info!(
+                "CBS expanded {} constraint tree nodes, aborting",
+                self.max_nodes
+            );
+            self.finish(SearchOutcome::Aborted);
+            return;
+        }
+
+        self.high_level_nodes += 1;
+
+        let Some(conflict) = find_conflict(&self.constraint_tree[index].paths) else {
+            self.paths = self.constraint_tree[index].paths.clone();
+            self.finish(SearchOutcome::Found);
+            return;
+        };
+
+        // Each child forbids one of the two agents its part in the conflict
+        let node = &self.constraint_tree[index];
+        let branches = match conflict {
+            Conflict::Cell(a, b, position, time) => [a, b].map(|agent| {
+                let mut constraints = node.constraints[agent].clone();
+                constraints.reserve_cell(position, time);
+                (agent, constraints)
+            }),
+            Conflict::Swap(a, b, time) => [a, b].map(|agent| {
+                let path = &node.paths[agent];
+                let mut constraints = node.constraints[agent].clone();
+                constraints.reserve_move(
+                    position_at(path, time as usize - 1),
+                    position_at(path, time as usize),
+                    time,
+                );
+                (agent, constraints)
+            }),
+        };
+
+        for (agent, agent_constraints) in branches {
+            let Some(path) = self.plan_agent(agent, &agent_constraints) else {
+                continue;
+            };
+            let node = &self.constraint_tree[index];
+            let mut constraints = node.constraints.clone();
+            let mut paths = node.paths.clone();
+            constraints[agent] = agent_constraints;
+            paths[agent] = path;
+            self.push_node(ConstraintNode { constraints, paths });
+        }
+    }
+
+    fn plan_agent(
+        &mut self,
+        agent: usize,
+        constraints: &ReservationTable,
+    ) -> Option<Vec<GridPosition>> {
+        plan_path(
+            &self.maze,
+            &self.agents[agent],
+            constraints,
+            &self.distances[agent],
+            &mut self.expanded_nodes,
+        )
+    }
+
+    fn push_node(&mut self, node: ConstraintNode) {
+        let priority = (sum_of_costs(&node.paths), count_conflicts(&node.paths));
+        self.open_nodes.push(Reverse((
+            priority.0,
+            priority.1,
+            self.constraint_tree.len(),
+        )));
+        self.constraint_tree.push(node);
+    }
+
+    fn finish(&mut self, outcome: SearchOutcome) {
+        self.outcome = outcome;
+        // The constraint tree is no longer needed once finished
+        self.constraint_tree.clear();
+        self.open_nodes.clear();
+
+        log::info!(
+            "{} result for {} agents - {:?}",
+            self.name(),
+            self.agents.len(),
+            outcome
+        );
+        if outcome == SearchOutcome::Found {
+            log::info!(
+                "Sum of costs: {}, makespan: {}",
+                self.sum_of_costs(),
+                self.makespan()
+            );
+        }
+        log::info!(
+            "Number of explored nodes: {}{}",
+            self.expanded_nodes,
+            if self.algorithm == MapfAlgorithm::ConflictBasedSearch {
+                format!(", constraint tree nodes: {}", self.high_level_nodes)
+            } else {
+                String::new()
+            }
+        );
+    }
+
+    /// Stop a search that is still in progress
+    pub fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.paths.clear();
+            self.finish(SearchOutcome::Aborted);
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.algorithm.name()
+    }
+
+    pub fn algorithm(&self) -> MapfAlgorithm {
+        self.algorithm
+    }
+
+    pub fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    pub fn agents(&self) -> &[Agent] {
+        &self.agents
+    }
+
+    pub fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    /// Timed path of every agent, position i at time step i, empty unless paths were found.
+    /// Each path ends once its agent rests at its goal for good.
+    pub fn paths(&self) -> &[Vec<GridPosition>] {
+        &self.paths
+    }
+
+    /// Cell of an agent at a time step, its goal after it arrived
+    pub fn position_at(&self, agent: usize, time: usize) -> GridPosition {
+        position_at(&self.paths[agent], time)
+    }
+
+    /// Time steps all agents take until they rest at their goals
+    pub fn sum_of_costs(&self) -> usize {
+        sum_of_costs(&self.paths)
+    }
+
+    /// Time step by which all agents arrived
+    pub fn makespan(&self) -> usize {
+        self.paths
+            .iter()
+            .map(|path| path.len() - 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn expanded_nodes(&self) -> usize {
+        self.expanded_nodes
+    }
+
+    /// Constraint tree nodes expanded by CBS
+    pub fn high_level_nodes(&self) -> usize {
+        self.high_level_nodes
+    }
+}
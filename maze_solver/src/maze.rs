@@ -1,8 +1,9 @@
-use anyhow::Result;
-use ggez::graphics::Color;
+use std::ops::Add;
 
-pub const SCREEN_WIDTH: f32 = 1200.0;
-pub const SCREEN_HEIGHT: f32 = 1200.0;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::parser::*;
 
 pub const SEARCH_ORDER: [GridPosition; 4] = [
     // Up
@@ -15,25 +16,55 @@ pub const SEARCH_ORDER: [GridPosition; 4] = [
     GridPosition { x: 0, y: -1 },
 ];
 
-pub struct MazeColor(pub Color);
-
-impl MazeColor {
-    pub const EMPTY: Self = Self(Color::WHITE);
-    pub const WALL: Self = Self(Color::BLACK);
-    pub const START: Self = Self(Color::GREEN);
-    pub const END: Self = Self(Color::RED);
-    pub const EXPANDED: Self = Self(Color {
-        r: 0.5,
-        g: 0.5,
-        b: 0.5,
-        a: 0.5,
-    });
-    // pub const TO_BE_EXPANDED: Self = Self(Color::CYAN);
-    pub const PATH: Self = Self(Color::MAGENTA);
-    pub const PATH_TRACE: Self = Self(Color::RED);
+/// Diagonal moves appended to `SEARCH_ORDER` for 8-connected mazes
+pub const DIAGONAL_SEARCH_ORDER: [GridPosition; 4] = [
+    // Up right
+    GridPosition { x: -1, y: 1 },
+    // Down right
+    GridPosition { x: 1, y: 1 },
+    // Down left
+    GridPosition { x: 1, y: -1 },
+    // Up left
+    GridPosition { x: -1, y: -1 },
+];
+
+/// When a diagonal move past walls is allowed, checked against the two orthogonal neighbours
+/// shared by the source and destination cells
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiagonalMovement {
+    Always,
+    /// Forbids squeezing between two walls
+    IfAtMostOneWall,
+    /// Forbids cutting any wall corner
+    OnlyWhenNoWalls,
 }
 
+/// Movement model of the maze graph
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Connectivity {
+    Four,
+    Eight(DiagonalMovement),
+}
+
+impl std::str::FromStr for Connectivity {
+    type Err = anyhow::Error;
+
+    /// "4", "8" (no corner cutting), "8-squeeze" (at most one wall) or "8-always"
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "4" => Ok(Self::Four),
+            "8" => Ok(Self::Eight(DiagonalMovement::OnlyWhenNoWalls)),
+            "8-squeeze" => Ok(Self::Eight(DiagonalMovement::IfAtMostOneWall)),
+            "8-always" => Ok(Self::Eight(DiagonalMovement::Always)),
+            _ => Err(anyhow!(
+                "Unknown connectivity: {}, expected 4, 8, 8-squeeze or 8-always",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
 pub struct GridPosition {
     pub x: i32,
     pub y: i32,
@@ -43,40 +74,84 @@ impl GridPosition {
     pub fn new(x: i32, y: i32) -> Self {
         GridPosition { x, y }
     }
+}
+
+impl std::str::FromStr for GridPosition {
+    type Err = anyhow::Error;
+
+    /// Parse "x,y"
+    fn from_str(s: &str) -> Result<Self> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| anyhow!("Invalid position \"{}\", expected x,y", s))?;
+        Ok(Self::new(x.trim().parse()?, y.trim().parse()?))
+    }
+}
 
-    pub fn add(self, other: &Self) -> Self {
+impl Add for GridPosition {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
         Self::new(self.x + other.x, self.y + other.y)
     }
 }
 
+pub const CELL_EMPTY: u8 = 0;
+pub const CELL_WALL: u8 = 1;
+
 #[derive(Clone)]
 pub struct Maze {
+    /// 0 - empty (cost 1), 1 - wall, >= 2 - terrain with the value as its traversal cost
     pub grid: Vec<Vec<u8>>,
+
+    /// Start position marked in the maze file, the cell itself is stored as empty
+    pub position_start: Option<GridPosition>,
+    /// Goal position marked in the maze file, the cell itself is stored as empty
+    pub position_end: Option<GridPosition>,
+
+    pub connectivity: Connectivity,
 }
 
 impl Maze {
+    /// Parse a maze in any format supported by the `parser` module
     pub fn new_from_string(input: &str) -> Result<Maze> {
-        let grid = input
-            .trim()
-            .split('\n')
-            .map(|row| {
-                row.trim()
-                    .strip_prefix('[')
-                    .and_then(|row| row.strip_suffix("],"))
-                    // Manually remove leading '[' and ending ']'
-                    .unwrap_or_else(|| &row[2..row.len() - 1])
-                    .split(',')
-                    .map(|elem| elem.trim().parse().unwrap())
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+        let maze = parse_maze(input)?;
 
         log::info!("Parsed maze grid:");
-        grid.iter().for_each(|row| {
+        maze.grid.iter().for_each(|row| {
             log::info!("{:?}", row);
         });
 
-        Ok(Maze { grid })
+        Ok(maze)
+    }
+
+    /// Check that a start/goal position is inside the maze and not a wall
+    pub fn validate_position(&self, position: &GridPosition, name: &str) -> Result<()> {
+        if self.is_cell_valid(position) {
+            return Ok(());
+        }
+
+        if position.x < 0
+            || position.x >= self.grid.len() as _
+            || position.y < 0
+            || position.y >= self.grid[0].len() as _
+        {
+            Err(anyhow!(
+                "{} position ({},{}) is out of bounds, maze is {}x{}",
+                name,
+                position.x,
+                position.y,
+                self.grid.len(),
+                self.grid[0].len()
+            ))
+        } else {
+            Err(anyhow!(
+                "{} position ({},{}) is on a wall",
+                name,
+                position.x,
+                position.y
+            ))
+        }
     }
 
     /// Whether cell position is valid and not a wall
@@ -85,7 +160,141 @@ impl Maze {
             && position.x < self.grid.len() as _
             && position.y >= 0
             && position.y < self.grid[0].len() as _
-            && self.grid[position.x as usize][position.y as usize] == 0
+            && self.grid[position.x as usize][position.y as usize] != CELL_WALL
+    }
+
+    /// Whether any sequence of valid moves leads from one cell to the other, by flood filling from `from`
+    pub fn is_reachable(&self, from: &GridPosition, to: &GridPosition) -> bool {
+        if !self.is_cell_valid(from) {
+            return false;
+        }
+
+        let mut visited = vec![vec![false; self.grid[0].len()]; self.grid.len()];
+        visited[from.x as usize][from.y as usize] = true;
+        let mut stack = vec![*from];
+        while let Some(position) = stack.pop() {
+            if position == *to {
+                return true;
+            }
+            for cell in self.neighbours(&position) {
+                if !visited[cell.x as usize][cell.y as usize] {
+                    visited[cell.x as usize][cell.y as usize] = true;
+                    stack.push(cell);
+                }
+            }
+        }
+        false
+    }
+
+    /// Valid cells reachable in one move, orthogonal moves first in `SEARCH_ORDER`
+    pub fn neighbours(&self, position: &GridPosition) -> impl Iterator<Item = GridPosition> + '_ {
+        let position = *position;
+        let diagonals: &[GridPosition] = match self.connectivity {
+            Connectivity::Four => &[],
+            Connectivity::Eight(_) => &DIAGONAL_SEARCH_ORDER,
+        };
+
+        SEARCH_ORDER
+            .iter()
+            .chain(diagonals)
+            .filter(move |delta| self.is_move_valid(&position, delta))
+            .map(move |delta| position + *delta)
+    }
+
+    /// Whether a single move by `delta`, one of the moves of the maze connectivity, is allowed by the walls
+    pub fn is_move_valid(&self, position: &GridPosition, delta: &GridPosition) -> bool {
+        if !self.is_cell_valid(&(*position + *delta)) {
+            return false;
+        }
+
+        let Connectivity::Eight(diagonal_movement) = self.connectivity else {
+            return true;
+        };
+        if delta.x == 0 || delta.y == 0 {
+            return true;
+        }
+
+        let walls = [
+            GridPosition::new(position.x + delta.x, position.y),
+            GridPosition::new(position.x, position.y + delta.y),
+        ]
+        .iter()
+        .filter(|cell| !self.is_cell_valid(cell))
+        .count();
+
+        match diagonal_movement {
+            DiagonalMovement::Always => true,
+            DiagonalMovement::IfAtMostOneWall => walls < 2,
+            DiagonalMovement::OnlyWhenNoWalls => walls == 0,
+        }
+    }
+
+    /// Whether the straight segment between the centres of two cells only crosses free cells.
+    /// Crossing exactly through a cell corner needs both cells beside the corner to be free.
+    pub fn has_line_of_sight(&self, from: &GridPosition, to: &GridPosition) -> bool {
+        let (nx, ny) = ((to.x - from.x).abs(), (to.y - from.y).abs());
+        let (sx, sy) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+
+        let mut position = *from;
+        let (mut ix, mut iy) = (0, 0);
+        while ix < nx || iy < ny {
+            // Compare where the segment leaves the current cell along each axis, scaled to stay in integers
+            let decision = (1 + 2 * ix) * ny - (1 + 2 * iy) * nx;
+            if decision == 0 {
+                if !self.is_cell_valid(&GridPosition::new(position.x + sx, position.y))
+                    || !self.is_cell_valid(&GridPosition::new(position.x, position.y + sy))
+                {
+                    return false;
+                }
+                position = position + GridPosition::new(sx, sy);
+                ix += 1;
+                iy += 1;
+            } else if decision < 0 {
+                position.x += sx;
+                ix += 1;
+            } else {
+                position.y += sy;
+                iy += 1;
+            }
+
+            if !self.is_cell_valid(&position) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Cost of moving between adjacent cells, diagonal moves cost sqrt(2) times the destination cell cost
+    pub fn move_cost(&self, from: &GridPosition, to: &GridPosition) -> f32 {
+        if from.x != to.x && from.y != to.y {
+            std::f32::consts::SQRT_2 * self.cell_cost(to)
+        } else {
+            self.cell_cost(to)
+        }
+    }
+
+    /// Cost of moving into a valid cell
+    pub fn cell_cost(&self, position: &GridPosition) -> f32 {
+        match self.grid[position.x as usize][position.y as usize] {
+            CELL_EMPTY => 1.0,
+            cost => cost as f32,
+        }
+    }
+
+    /// Highest traversal cost of any cell, 1 for unweighted mazes
+    pub fn max_cell_cost(&self) -> f32 {
+        self.grid
+            .iter()
+            .flatten()
+            .filter(|&&cell| cell != CELL_WALL)
+            .map(|&cell| if cell == CELL_EMPTY { 1.0 } else { cell as f32 })
+            .fold(1.0, f32::max)
+    }
+
+    /// Total cost of a start -> end path, the start cell itself is free
+    pub fn path_cost(&self, path: &[GridPosition]) -> f32 {
+        path.windows(2)
+            .fold(0.0, |cost, step| cost + self.move_cost(&step[0], &step[1]))
     }
 }
 
@@ -105,7 +314,13 @@ impl MazePathEdges {
     }
 
     pub fn add_edge(&mut self, source: &GridPosition, dest: &GridPosition) {
-        self.edges[dest.x as usize][dest.y as usize] = source.clone();
+        self.edges[dest.x as usize][dest.y as usize] = *source;
+    }
+
+    /// Source node of a visited position
+    pub fn parent(&self, position: &GridPosition) -> Option<GridPosition> {
+        let source = self.edges[position.x as usize][position.y as usize];
+        (source != INVALID_POSITION).then_some(source)
     }
 
     pub fn build_path(
@@ -114,9 +329,9 @@ impl MazePathEdges {
         end: &GridPosition,
     ) -> Result<Vec<GridPosition>> {
         let mut path = Vec::new();
-        path.push(end.clone());
+        path.push(*end);
 
-        let mut current_dest = end.clone();
+        let mut current_dest = *end;
         let mut current_source = self.edges[current_dest.x as usize][current_dest.y as usize];
 
         while current_source != INVALID_POSITION && &current_dest != start {
@@ -134,14 +349,3 @@ impl MazePathEdges {
         Ok(path)
     }
 }
-
-pub trait MazeSolverDrawable {
-    fn update(&mut self);
-
-    fn expanded_nodes(&self) -> &Vec<GridPosition>;
-    fn start(&self) -> &GridPosition;
-    fn end(&self) -> &GridPosition;
-
-    fn is_complete(&self) -> bool;
-    fn path(&self) -> &Vec<GridPosition>;
-}
@@ -0,0 +1,461 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    astar::{open_count, open_positions, SearchNode},
+    maze::*,
+    solver::*,
+};
+
+const STEPS_PER_UPDATE: usize = 10;
+
+/// Largest number of goals whose visiting order is solved exactly, the dynamic program takes O(2^n * n^2) time
+const MAX_EXACT_GOALS: usize = 12;
+
+/// How several goals are searched for
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GoalMode {
+    /// Reach whichever goal is nearest
+    #[default]
+    Nearest,
+    /// Visit every goal, in the order with the cheapest total path
+    All,
+}
+
+impl GoalMode {
+    /// Solver for several goals, None if the algorithm cannot search for the nearest of several goals.
+    /// Visiting all goals does not depend on the algorithm. Panics if `goals` is empty.
+    pub fn new_solver(
+        &self,
+        algorithm: MazeSolveAlgorithm,
+        maze: Maze,
+        position_start: GridPosition,
+        goals: Vec<GridPosition>,
+        options: &SolverOptions,
+    ) -> Option<Box<dyn MazeSolverDrawable>> {
+        match self {
+            Self::Nearest => {
+                algorithm.new_nearest_goal_solver(maze, position_start, goals, options)
+            }
+            Self::All => Some(Box::new(MazeSolverAllGoals::new(
+                maze,
+                position_start,
+                goals,
+            ))),
+        }
+    }
+}
+
+impl std::str::FromStr for GoalMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "nearest" | "any" => Ok(Self::Nearest),
+            "all" => Ok(Self::All),
+            _ => Err(anyhow::anyhow!(
+                "Unknown goal mode: {}, expected nearest or all",
+                s
+            )),
+        }
+    }
+}
+
+/// Uniform-cost search from one waypoint that runs until every goal is settled
+struct DistanceSearch {
+    search_grid: Vec<Vec<f32>>,
+    closed_grid: Vec<Vec<bool>>,
+    current_search_positions: BinaryHeap<Reverse<SearchNode>>,
+    path_edges: MazePathEdges,
+
+    /// Goals whose cost is not final yet
+    remaining_goals: usize,
+}
+
+impl DistanceSearch {
+    fn new(maze: &Maze, source: GridPosition, goals: &[GridPosition]) -> Self {
+        let mut search_grid = vec![vec![f32::MAX; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[source.x as usize][source.y as usize] = 0.0;
+
+        let mut current_search_positions = BinaryHeap::new();
+        current_search_positions.push(Reverse(SearchNode::new(0.0, 0.0, 0.0, source)));
+
+        // Goals listed more than once are only settled once
+        let mut remaining_goals = goals.to_vec();
+        remaining_goals.sort_by_key(|goal| (goal.x, goal.y));
+        remaining_goals.dedup();
+
+        Self {
+            search_grid,
+            closed_grid: vec![vec![false; maze.grid[0].len()]; maze.grid.len()],
+            current_search_positions,
+            path_edges: MazePathEdges::new_with_maze(maze),
+            remaining_goals: remaining_goals.len(),
+        }
+    }
+
+    /// Expand the cheapest open node, returns it unless the search is finished
+    fn expand(&mut self, maze: &Maze, goals: &[GridPosition]) -> Option<GridPosition> {
+        loop {
+            if self.remaining_goals == 0 {
+                return None;
+            }
+            let start = self.current_search_positions.pop()?.0;
+            if self.closed_grid[start.position.x as usize][start.position.y as usize] {
+                continue;
+            }
+            self.closed_grid[start.position.x as usize][start.position.y as usize] = true;
+
+            if goals.contains(&start.position) {
+                self.remaining_goals -= 1;
+            }
+
+            for cell in maze.neighbours(&start.position) {
+                let g = start.g + maze.move_cost(&start.position, &cell);
+                if g < self.search_grid[cell.x as usize][cell.y as usize] {
+                    self.search_grid[cell.x as usize][cell.y as usize] = g;
+                    self.path_edges.add_edge(&start.position, &cell);
+                    self.current_search_positions
+                        .push(Reverse(SearchNode::new(g, 0.0, g, cell)));
+                }
+            }
+            return Some(start.position);
+        }
+    }
+
+    /// Cost of a settled goal, f32::MAX if it is unreachable
+    fn cost(&self, goal: &GridPosition) -> f32 {
+        self.search_grid[goal.x as usize][goal.y as usize]
+    }
+}
+
+/// Visit every goal, starting from the start position. Costs between the start and all goals are found with one
+/// uniform-cost search per waypoint, then the cheapest visiting order is solved exactly for up to `MAX_EXACT_GOALS`
+/// goals and approximated with nearest neighbour and 2-opt for more.
+pub struct MazeSolverAllGoals {
+    maze: Maze,
+    position_start: GridPosition,
+    /// Last goal of the visiting order once found
+    position_end: GridPosition,
+    goals: Vec<GridPosition>,
+
+    /// Waypoint 0 is the start and waypoint i > 0 is goal i - 1, searches run from every waypoint in turn
+    current_search: Option<DistanceSearch>,
+    /// Path trees of the finished searches, indexed by waypoint
+    path_trees: Vec<MazePathEdges>,
+    /// costs[i][j] from waypoint i to goal j
+    costs: Vec<Vec<f32>>,
+
+    /// Goal indices in visiting order
+    goal_order: Vec<usize>,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    expanded_nodes: Vec<GridPosition>,
+    /// Expansions of the search from the current waypoint
+    search_expanded_nodes: Vec<GridPosition>,
+}
+
+impl MazeSolverAllGoals {
+    /// Panics if `goals` is empty
+    pub fn new(maze: Maze, position_start: GridPosition, goals: Vec<GridPosition>) -> Self {
+        assert!(
+            !goals.is_empty(),
+            "Visiting all goals needs at least 1 goal"
+        );
+        let current_search = Some(DistanceSearch::new(&maze, position_start, &goals));
+
+        Self {
+            maze,
+            position_start,
+            position_end: goals[0],
+            goals,
+
+            current_search,
+            path_trees: Vec::new(),
+            costs: Vec::new(),
+
+            goal_order: Vec::new(),
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+            search_expanded_nodes: Vec::new(),
+        }
+    }
+
+    /// Goal indices in the order they are visited, empty unless a path was found
+    pub fn goal_order(&self) -> &[usize] {
+        &self.goal_order
+    }
+
+    /// 1 step of search, expanding 1 node of the search from the current waypoint
+    fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+        let Some(search) = self.current_search.as_mut() else {
+            return;
+        };
+
+        if let Some(position) = search.expand(&self.maze, &self.goals) {
+            self.expanded_nodes.push(position);
+            self.search_expanded_nodes.push(position);
+            return;
+        }
+
+        let search = self.current_search.take().unwrap();
+        self.costs
+            .push(self.goals.iter().map(|goal| search.cost(goal)).collect());
+        self.path_trees.push(search.path_edges);
+
+        // Cells reachable from one waypoint are reachable from all of them, the start's search decides
+        if self.costs[0].contains(&f32::MAX) {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        }
+
+        let waypoint = self.path_trees.len();
+        if waypoint <= self.goals.len() {
+            self.current_search = Some(DistanceSearch::new(
+                &self.maze,
+                self.goals[waypoint - 1],
+                &self.goals,
+            ));
+            self.search_expanded_nodes.clear();
+            return;
+        }
+
+        self.goal_order = if self.goals.len() <= MAX_EXACT_GOALS {
+            self.exact_order()
+        } else {
+            self.approximate_order()
+        };
+        self.build_path();
+        self.outcome = SearchOutcome::Found;
+
+        log::info!(
+            "Visiting goals in order {:?}{}",
+            self.goal_order
+                .iter()
+                .map(|&goal| (self.goals[goal].x, self.goals[goal].y))
+                .collect::<Vec<_>>(),
+            if self.goals.len() <= MAX_EXACT_GOALS {
+                ""
+            } else {
+                ", approximated"
+            }
+        );
+        print_result_path(self);
+    }
+
+    /// Cost from waypoint `from`, None for the start, to goal `to`
+    fn cost(&self, from: Option<usize>, to: usize) -> f32 {
+        self.costs[from.map_or(0, |goal| goal + 1)][to]
+    }
+
+    fn order_cost(&self, order: &[usize]) -> f32 {
+        let mut from = None;
+        let mut cost = 0.0;
+        for &goal in order {
+            cost += self.cost(from, goal);
+            from = Some(goal);
+        }
+        cost
+    }
+
+    /// Held-Karp: cheapest cost of visiting each subset of goals ending at each goal
+    fn exact_order(&self) -> Vec<usize> {
+        let n = self.goals.len();
+        let mut best = vec![vec![f32::MAX; n]; 1 << n];
+        let mut previous = vec![vec![None; n]; 1 << n];
+        for goal in 0..n {
+            best[1 << goal][goal] = self.cost(None, goal);
+        }
+
+        for visited in 1..(1usize << n) {
+            for last in (0..n).filter(|&last| visited & (1 << last) != 0) {
+                let cost = best[visited][last];
+                if cost == f32::MAX {
+                    continue;
+                }
+                for next in (0..n).filter(|&next| visited & (1 << next) == 0) {
+                    let next_visited = visited | (1 << next);
+                    let next_cost = cost + self.cost(Some(last), next);
+                    if next_cost < best[next_visited][next] {
+                        best[next_visited][next] = next_cost;
+                        previous[next_visited][next] = Some(last);
+                    }
+                }
+            }
+        }
+
+        let all = (1 << n) - 1;
+        let mut last = (0..n)
+            .min_by(|&a, &b| best[all][a].total_cmp(&best[all][b]))
+            .unwrap();
+        let mut visited = all;
+        let mut order = vec![last];
+        while let Some(goal) = previous[visited][last] {
+            visited &= !(1 << last);
+            last = goal;
+            order.push(last);
+        }
+        order.reverse();
+        order
+    }
+
+    /// Nearest neighbour order improved by reversing segments while that makes it cheaper
+    fn approximate_order(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.goals.len());
+        let mut remaining = (0..self.goals.len()).collect::<Vec<_>>();
+        let mut from = None;
+        while !remaining.is_empty() {
+            let index = (0..remaining.len())
+                .min_by(|&a, &b| {
+                    self.cost(from, remaining[a])
+                        .total_cmp(&self.cost(from, remaining[b]))
+                })
+                .unwrap();
+            let goal = remaining.swap_remove(index);
+            order.push(goal);
+            from = Some(goal);
+        }
+
+        // Costs are asymmetric with terrain, so every candidate is costed in full
+        let mut cost = self.order_cost(&order);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..order.len() {
+                for j in i + 1..order.len() {
+                    order[i..=j].reverse();
+                    let candidate_cost = self.order_cost(&order);
+                    if candidate_cost < cost - 1e-3 {
+                        cost = candidate_cost;
+                        improved = true;
+                    } else {
+                        order[i..=j].reverse();
+                    }
+                }
+            }
+        }
+        order
+    }
+
+    /// Stitch the paths between consecutive waypoints of the visiting order
+    fn build_path(&mut self) {
+        let mut from = self.position_start;
+        let mut from_waypoint = 0;
+        self.complete_path = vec![from];
+        for &goal in &self.goal_order {
+            let to = self.goals[goal];
+            let leg = self.path_trees[from_waypoint]
+                .build_path(&from, &to)
+                .unwrap();
+            self.complete_path.extend_from_slice(&leg[1..]);
+
+            from = to;
+            from_waypoint = goal + 1;
+        }
+        self.position_end = from;
+    }
+}
+
+impl MazeSolver for MazeSolverAllGoals {
+    fn name(&self) -> &'static str {
+        "Visit all goals"
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    fn goals(&self) -> &[GridPosition] {
+        &self.goals
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            self.current_search = None;
+            print_result_path(self);
+        }
+    }
+
+    fn path_cost(&self) -> f32 {
+        self.maze.path_cost(&self.complete_path)
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverAllGoals {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn drawn_expanded_nodes(&self) -> &[GridPosition] {
+        &self.search_expanded_nodes
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        self.current_search
+            .as_ref()
+            .map(|search| {
+                open_positions(
+                    &search.current_search_positions,
+                    &search.search_grid,
+                    &search.closed_grid,
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    fn frontier_len(&self) -> usize {
+        self.current_search.as_ref().map_or(0, |search| {
+            open_count(
+                &search.current_search_positions,
+                &search.search_grid,
+                &search.closed_grid,
+            )
+        })
+    }
+
+    /// Costs from the waypoint the current search started at
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        let g = self.current_search.as_ref()?.search_grid[position.x as usize][position.y as usize];
+        (g != f32::MAX).then_some(CellValues { g, h: 0.0, f: g })
+    }
+
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        self.current_search
+            .as_ref()
+            .map(|search| &search.path_edges)
+    }
+}
@@ -0,0 +1,450 @@
+//! Maze file parsing.
+//!
+//! Supported formats, detected from the first non-whitespace character:
+//! - Python style nested lists: `[[0, 1, 0], [S, 4, G]]`
+//! - Whitespace separated digit grids: one row per line, `0 1 0`
+//! - ASCII art: one row per line, `#` wall, `.` empty, `S` start, `G` goal
+//!
+//! In the numeric formats `S` and `G` mark the start and goal cells, which are stored as empty cells.
+
+use std::{fmt, iter::Peekable, str::Chars};
+
+use crate::maze::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MazeFormat {
+    PythonList,
+    DigitGrid,
+    Ascii,
+}
+
+impl MazeFormat {
+    pub fn detect(input: &str) -> Option<Self> {
+        let first_line = input.lines().map(str::trim).find(|line| !line.is_empty())?;
+        if first_line.starts_with('[') {
+            Some(Self::PythonList)
+        } else if first_line
+            .chars()
+            .all(|c| matches!(c, '#' | '.' | 'S' | 'G'))
+        {
+            Some(Self::Ascii)
+        } else if first_line.starts_with(|c: char| c.is_ascii_digit() || c == 'S' || c == 'G') {
+            Some(Self::DigitGrid)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse failure with the 1-based line and column it occurred at
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Token {
+    Cell(u8),
+    Start,
+    End,
+}
+
+/// Start or goal marker with its source location
+struct Marker {
+    token: Token,
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+/// Parsed row with the location it started at
+#[derive(Default)]
+struct Row {
+    cells: Vec<u8>,
+    markers: Vec<Marker>,
+    line: usize,
+    column: usize,
+}
+
+impl Row {
+    fn new(line: usize, column: usize) -> Self {
+        Self {
+            line,
+            column,
+            ..Default::default()
+        }
+    }
+
+    fn push(&mut self, token: Token, line: usize, column: usize) {
+        match token {
+            Token::Cell(value) => self.cells.push(value),
+            Token::Start | Token::End => {
+                self.markers.push(Marker {
+                    token,
+                    index: self.cells.len(),
+                    line,
+                    column,
+                });
+                self.cells.push(CELL_EMPTY);
+            }
+        }
+    }
+}
+
+/// Parse a rectangular maze in any supported format
+pub fn parse_maze(input: &str) -> Result<Maze, ParseError> {
+    let rows = match MazeFormat::detect(input) {
+        Some(MazeFormat::PythonList) => parse_python_list(input)?,
+        Some(MazeFormat::DigitGrid) => parse_digit_grid(input)?,
+        Some(MazeFormat::Ascii) => parse_ascii(input)?,
+        None => {
+            let (line, column) = first_non_whitespace(input);
+            return Err(ParseError::new(line, column, "unrecognised maze format"));
+        }
+    };
+
+    let mut position_start = None;
+    let mut position_end = None;
+    for (x, row) in rows.iter().enumerate() {
+        for marker in &row.markers {
+            let (position, name) = match marker.token {
+                Token::Start => (&mut position_start, "start"),
+                _ => (&mut position_end, "goal"),
+            };
+            if position.is_some() {
+                return Err(ParseError::new(
+                    marker.line,
+                    marker.column,
+                    format!("maze contains more than one {} marker", name),
+                ));
+            }
+            *position = Some(GridPosition::new(x as _, marker.index as _));
+        }
+    }
+
+    Ok(Maze {
+        grid: validate_rectangular(rows)?,
+        position_start,
+        position_end,
+        connectivity: Connectivity::Four,
+    })
+}
+
+fn validate_rectangular(rows: Vec<Row>) -> Result<Vec<Vec<u8>>, ParseError> {
+    let Some(first) = rows.first() else {
+        return Err(ParseError::new(1, 1, "maze is empty"));
+    };
+
+    let columns = first.cells.len();
+    if columns == 0 {
+        return Err(ParseError::new(
+            first.line,
+            first.column,
+            "maze row is empty",
+        ));
+    }
+
+    for row in &rows {
+        if row.cells.len() != columns {
+            return Err(ParseError::new(
+                row.line,
+                row.column,
+                format!(
+                    "row has {} cells, expected {} to match the first row",
+                    row.cells.len(),
+                    columns
+                ),
+            ));
+        }
+    }
+
+    Ok(rows.into_iter().map(|row| row.cells).collect())
+}
+
+fn first_non_whitespace(input: &str) -> (usize, usize) {
+    for (line, text) in input.lines().enumerate() {
+        if let Some(column) = text.chars().position(|c| !c.is_whitespace()) {
+            return (line + 1, column + 1);
+        }
+    }
+    (1, 1)
+}
+
+/// Character stream tracking the current line and column
+struct Scanner<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.line, self.column, message)
+    }
+
+    fn describe_next(&mut self) -> String {
+        match self.peek() {
+            Some(c) => format!("'{}'", c),
+            None => String::from("end of input"),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.next();
+            Ok(())
+        } else {
+            let found = self.describe_next();
+            Err(self.error(format!("expected '{}', found {}", expected, found)))
+        }
+    }
+
+    /// Consume `c` if it is the next non-whitespace character
+    fn accept(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&mut self) -> Result<Token, ParseError> {
+        self.skip_whitespace();
+        let (line, column) = (self.line, self.column);
+
+        let mut text = String::new();
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric()) {
+            text.push(c);
+            self.next();
+        }
+
+        if text.is_empty() {
+            let found = self.describe_next();
+            return Err(self.error(format!("expected a cell value, found {}", found)));
+        }
+        parse_token(&text, line, column)
+    }
+}
+
+fn parse_token(token: &str, line: usize, column: usize) -> Result<Token, ParseError> {
+    match token {
+        "S" => return Ok(Token::Start),
+        "G" => return Ok(Token::End),
+        _ => {}
+    }
+
+    if !token.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ParseError::new(
+            line,
+            column,
+            format!("invalid cell value '{}'", token),
+        ));
+    }
+    token.parse().map(Token::Cell).map_err(|_| {
+        ParseError::new(
+            line,
+            column,
+            format!("cell value {} is out of range (0-255)", token),
+        )
+    })
+}
+
+/// `[[0, 1], [1, 0]]`, rows may span lines and trailing commas are allowed
+fn parse_python_list(input: &str) -> Result<Vec<Row>, ParseError> {
+    let mut scanner = Scanner::new(input);
+    let mut rows = Vec::new();
+
+    scanner.expect('[')?;
+    loop {
+        scanner.skip_whitespace();
+        if rows.is_empty() || scanner.peek() != Some(']') {
+            rows.push(parse_python_list_row(&mut scanner)?);
+        }
+        if !scanner.accept(',') {
+            break;
+        }
+    }
+    scanner.expect(']')?;
+
+    scanner.skip_whitespace();
+    if scanner.peek().is_some() {
+        let found = scanner.describe_next();
+        return Err(scanner.error(format!("unexpected {} after the end of the maze", found)));
+    }
+
+    Ok(rows)
+}
+
+fn parse_python_list_row(scanner: &mut Scanner) -> Result<Row, ParseError> {
+    scanner.skip_whitespace();
+    let (line, column) = (scanner.line, scanner.column);
+    scanner.expect('[')?;
+
+    let mut row = Row::new(line, column);
+    loop {
+        scanner.skip_whitespace();
+        if scanner.peek() == Some(']') {
+            break;
+        }
+        let (line, column) = (scanner.line, scanner.column);
+        let token = scanner.token()?;
+        row.push(token, line, column);
+        if !scanner.accept(',') {
+            break;
+        }
+    }
+    scanner.expect(']')?;
+
+    Ok(row)
+}
+
+/// Non-empty lines of the input with their 1-based line numbers
+fn non_empty_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(line, text)| (line + 1, text.trim_end()))
+        .filter(|(_, text)| !text.is_empty())
+}
+
+/// `0 1 0`, one row per line
+fn parse_digit_grid(input: &str) -> Result<Vec<Row>, ParseError> {
+    let mut rows = Vec::new();
+    for (line, text) in non_empty_lines(input) {
+        let column = text.chars().position(|c| !c.is_whitespace()).unwrap_or(0) + 1;
+        let mut row = Row::new(line, column);
+        let mut token_column = None;
+        let mut token = String::new();
+
+        // Trailing space terminates the last token
+        for (column, c) in text.chars().chain(std::iter::once(' ')).enumerate() {
+            if c.is_whitespace() {
+                if let Some(token_column) = token_column.take() {
+                    row.push(parse_token(&token, line, token_column)?, line, token_column);
+                    token.clear();
+                }
+            } else {
+                token_column.get_or_insert(column + 1);
+                token.push(c);
+            }
+        }
+
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// `#.S.G`, one row per line. Indentation shared by all rows is ignored, as format detection does.
+fn parse_ascii(input: &str) -> Result<Vec<Row>, ParseError> {
+    let indent = non_empty_lines(input)
+        .map(|(_, text)| text.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+
+    let mut rows = Vec::new();
+    for (line, text) in non_empty_lines(input) {
+        let mut row = Row::new(line, indent + 1);
+        for (column, c) in text.chars().enumerate().skip(indent) {
+            let token = match c {
+                '.' => Token::Cell(CELL_EMPTY),
+                '#' => Token::Cell(CELL_WALL),
+                'S' => Token::Start,
+                'G' => Token::End,
+                _ => {
+                    return Err(ParseError::new(
+                        line,
+                        column + 1,
+                        format!("unexpected character '{}', expected one of # . S G", c),
+                    ))
+                }
+            };
+            row.push(token, line, column + 1);
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Write a maze in the Python style list format, with the start and goal marked as `S` and `G`
+pub fn format_maze(maze: &Maze) -> String {
+    let rows = maze
+        .grid
+        .iter()
+        .enumerate()
+        .map(|(x, row)| {
+            let cells = row
+                .iter()
+                .enumerate()
+                .map(|(y, cell)| {
+                    let position = GridPosition::new(x as _, y as _);
+                    if maze.position_start == Some(position) {
+                        String::from("S")
+                    } else if maze.position_end == Some(position) {
+                        String::from("G")
+                    } else {
+                        cell.to_string()
+                    }
+                })
+                .collect::<Vec<_>>();
+            format!("[{}]", cells.join(", "))
+        })
+        .collect::<Vec<_>>();
+
+    format!("[{}]\n", rows.join(",\n "))
+}
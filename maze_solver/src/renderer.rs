@@ -1,53 +1,480 @@
-use ggez::graphics::{self, Canvas, Color, Rect};
+use std::ops::Range;
 
-use crate::maze::*;
+use ggez::{
+    glam::Vec2,
+    graphics::{self, Canvas, Color, DrawParam, InstanceArray, Mesh, MeshBuilder, Rect, Text},
+    Context as GgezContext, GameResult,
+};
+
+use crate::{generator::*, maze::*, solver::*};
+
+/// Initial window size, the window can be resized afterwards
+pub const SCREEN_WIDTH: f32 = 1200.0;
+pub const SCREEN_HEIGHT: f32 = 1200.0;
+
+/// Zooming in stops once cells are this large, unless they already fit larger
+const MAX_CELL_SIZE: f32 = 128.0;
+
+/// Cells per side of the square tiles the maze is batched in, an edit only rebuilds its tile
+const TILE_SIZE: usize = 64;
+
+/// Smallest cell size in pixels the g/h/f values are still legible at
+const MIN_VALUES_CELL_SIZE: f32 = 24.0;
+
+pub struct MazeColor(pub Color);
+
+impl MazeColor {
+    pub const EMPTY: Self = Self(Color::WHITE);
+    pub const WALL: Self = Self(Color::BLACK);
+    /// Colour of the most expensive terrain, cheaper terrain is blended towards EMPTY
+    pub const TERRAIN: Self = Self(Color {
+        r: 0.55,
+        g: 0.35,
+        b: 0.15,
+        a: 1.0,
+    });
+    pub const START: Self = Self(Color::GREEN);
+    pub const END: Self = Self(Color::RED);
+    pub const EXPANDED: Self = Self(Color {
+        r: 0.5,
+        g: 0.5,
+        b: 0.5,
+        a: 0.5,
+    });
+    /// Nodes expanded backwards from the goal by bidirectional searches
+    pub const EXPANDED_BACKWARD: Self = Self(Color {
+        r: 0.3,
+        g: 0.5,
+        b: 0.9,
+        a: 0.5,
+    });
+    /// Nodes a solver highlights, e.g. jump points
+    pub const HIGHLIGHTED: Self = Self(Color {
+        r: 1.0,
+        g: 0.6,
+        b: 0.1,
+        a: 0.8,
+    });
+    /// Expanded nodes once the search proved the goal unreachable
+    pub const EXPANDED_UNREACHABLE: Self = Self(Color {
+        r: 0.8,
+        g: 0.2,
+        b: 0.2,
+        a: 0.35,
+    });
+    /// Goal that cannot be reached
+    pub const END_UNREACHABLE: Self = Self(Color {
+        r: 0.35,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    });
+    /// Cells waiting to be expanded
+    pub const TO_BE_EXPANDED: Self = Self(Color {
+        r: 0.0,
+        g: 0.8,
+        b: 0.8,
+        a: 0.6,
+    });
+    /// Expansion order heatmap, from the first expanded node to the last
+    pub const HEATMAP_EARLY: Self = Self(Color {
+        r: 1.0,
+        g: 0.9,
+        b: 0.2,
+        a: 0.7,
+    });
+    pub const HEATMAP_LATE: Self = Self(Color {
+        r: 0.35,
+        g: 0.0,
+        b: 0.5,
+        a: 0.7,
+    });
+    pub const PARENT_ARROW: Self = Self(Color {
+        r: 0.1,
+        g: 0.1,
+        b: 0.6,
+        a: 0.8,
+    });
+    pub const CELL_VALUES: Self = Self(Color::BLACK);
+    // pub const PATH: Self = Self(Color::MAGENTA);
+    pub const PATH_TRACE: Self = Self(Color::RED);
+    /// Cells a maze generator is currently carving from
+    pub const GENERATION_ACTIVE: Self = Self(Color {
+        r: 0.2,
+        g: 0.6,
+        b: 1.0,
+        a: 1.0,
+    });
+}
+
+/// Agent colours of multi-agent paths, repeated for more agents
+const AGENT_COLORS: [Color; 8] = [
+    Color::new(0.9, 0.1, 0.1, 1.0),
+    Color::new(0.1, 0.4, 0.9, 1.0),
+    Color::new(0.1, 0.7, 0.2, 1.0),
+    Color::new(0.9, 0.6, 0.0, 1.0),
+    Color::new(0.6, 0.2, 0.8, 1.0),
+    Color::new(0.0, 0.7, 0.7, 1.0),
+    Color::new(0.9, 0.3, 0.6, 1.0),
+    Color::new(0.5, 0.35, 0.2, 1.0),
+];
+
+impl MazeColor {
+    pub fn agent(index: usize) -> Color {
+        AGENT_COLORS[index % AGENT_COLORS.len()]
+    }
+
+    /// Goal cell tint of an agent
+    pub fn agent_goal(index: usize) -> Color {
+        let Color { r, g, b, .. } = Self::agent(index);
+        Color::new(r, g, b, 0.35)
+    }
+}
+
+/// Extra information drawn over a search, each toggled separately in the viewer
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Overlays {
+    /// Cells waiting to be expanded
+    pub frontier: bool,
+    /// g, h and f of every reached cell
+    pub values: bool,
+    /// Expanded nodes shaded by expansion order instead of a single colour
+    pub heatmap: bool,
+    /// Arrows from reached cells towards their parents
+    pub parents: bool,
+}
+
+/// Block of maze cells drawn as 1 batch, rebuilt when any of its cells changes
+struct MazeTile {
+    /// Cells the instances were built from, row by row
+    cells: Vec<u8>,
+    instances: InstanceArray,
+}
+
+impl MazeTile {
+    fn new(
+        ctx: &GgezContext,
+        maze: &Maze,
+        rows: Range<usize>,
+        columns: Range<usize>,
+        max_cell_cost: f32,
+    ) -> Self {
+        let mut cells = Vec::with_capacity(rows.len() * columns.len());
+        let mut instances = Vec::new();
+        for x in rows {
+            let row = &maze.grid[x][columns.clone()];
+            cells.extend_from_slice(row);
+
+            // Runs of equal cells along a row share 1 instance
+            let mut start = 0;
+            while start < row.len() {
+                let cell = row[start];
+                let length = row[start..].iter().take_while(|&&c| c == cell).count();
+                instances.push(
+                    DrawParam::new()
+                        .dest(Vec2::new((columns.start + start) as f32, x as f32))
+                        .scale(Vec2::new(length as f32, 1.0))
+                        .color(Renderer::cell_color(cell, max_cell_cost)),
+                );
+                start += length;
+            }
+        }
+
+        let mut batch = InstanceArray::new(ctx, None);
+        batch.set(instances);
+        Self {
+            cells,
+            instances: batch,
+        }
+    }
+
+    /// Whether the maze still has the cells the tile was built from
+    fn is_current(&self, maze: &Maze, rows: Range<usize>, columns: Range<usize>) -> bool {
+        rows.zip(self.cells.chunks(columns.len()))
+            .all(|(x, cells)| maze.grid[x][columns.clone()] == *cells)
+    }
+}
+
+/// Cells drawn as 1 batch of instances. Instances are in grid coordinates, so zooming and panning
+/// only change the transform the batch is drawn with.
+#[derive(Default)]
+struct CellBatch {
+    instances: Option<InstanceArray>,
+    /// Cells pushed by `extend` and the last of them, telling a list that grew from a replaced one
+    len: usize,
+    last: Option<GridPosition>,
+    color: Option<Color>,
+}
+
+impl CellBatch {
+    fn instances(&mut self, ctx: &GgezContext) -> &mut InstanceArray {
+        self.instances
+            .get_or_insert_with(|| InstanceArray::new(ctx, None))
+    }
+
+    /// Replace all cells, for cell sets that change completely between frames
+    fn set(&mut self, ctx: &GgezContext, instances: impl IntoIterator<Item = DrawParam>) {
+        self.instances(ctx).set(instances);
+        self.color = None;
+    }
+
+    /// Push the cells a list gained since the last call, or all of them if the list was replaced
+    fn extend(&mut self, ctx: &GgezContext, cells: &[GridPosition], color: Color) {
+        let grown = self.color == Some(color)
+            && self.len <= cells.len()
+            && cells[..self.len].last() == self.last.as_ref();
+        if !grown {
+            self.instances(ctx).clear();
+            self.len = 0;
+        }
+
+        let pushed = self.len;
+        let instances = self.instances(ctx);
+        for cell in &cells[pushed..] {
+            instances.push(Renderer::cell_instance(cell, color));
+        }
+        self.len = cells.len();
+        self.last = cells.last().copied();
+        self.color = Some(color);
+    }
+
+    fn draw(&self, canvas: &mut Canvas, transform: DrawParam) {
+        if let Some(instances) = &self.instances {
+            if !instances.instances().is_empty() {
+                canvas.draw(instances, transform);
+            }
+        }
+    }
+}
 
 pub struct Renderer {
-    cell_width: f32,
-    cell_height: f32,
+    /// Part of the window the grid is drawn in
+    area: Rect,
+    /// Screen position of the top left corner of the grid, outside of the area when zoomed in
+    origin: Vec2,
+    /// Cells are square so the maze keeps its proportions
+    cell_size: f32,
+
+    grid_rows: i32,
+    grid_columns: i32,
+
+    /// Maze tiles row by row, built when first drawn
+    maze_tiles: Vec<Option<MazeTile>>,
+    /// Terrain colours are relative to the most expensive cell, tiles are rebuilt when it changes
+    maze_max_cell_cost: f32,
+
+    // Each batch can only be drawn once per frame, the last contents set would be drawn every time
+    expanded: CellBatch,
+    expanded_backward: CellBatch,
+    heatmap: CellBatch,
+    frontier: CellBatch,
+    highlighted: CellBatch,
+    path: CellBatch,
 }
 
 impl Renderer {
     pub fn new_with_maze(maze: &Maze) -> Self {
-        let cell_width = SCREEN_WIDTH / maze.grid.len() as f32;
-        let cell_height = SCREEN_HEIGHT / maze.grid[0].len() as f32;
+        Self::new_with_maze_in(maze, Rect::new(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT))
+    }
 
-        Self {
-            cell_width,
-            cell_height,
-        }
-    }
-
-    pub fn draw_maze(&self, canvas: &mut Canvas, maze: &Maze) {
-        for (x, row) in maze.grid.iter().enumerate() {
-            for (y, &cell) in row.iter().enumerate() {
-                match cell {
-                    0 => {
-                        self.draw_cell(
-                            canvas,
-                            &GridPosition::new(x as _, y as _),
-                            MazeColor::EMPTY.0,
-                        );
-                    }
-                    1 => {
-                        self.draw_cell(
-                            canvas,
-                            &GridPosition::new(x as _, y as _),
-                            MazeColor::WALL.0,
-                        );
-                    }
-                    _ => {}
+    /// Draw the maze grid into a part of the window, fitting the whole maze in it
+    pub fn new_with_maze_in(maze: &Maze, area: Rect) -> Self {
+        let mut renderer = Self {
+            area,
+            origin: Vec2::ZERO,
+            cell_size: 0.0,
+
+            grid_rows: maze.grid.len() as _,
+            grid_columns: maze.grid[0].len() as _,
+
+            maze_tiles: Vec::new(),
+            maze_max_cell_cost: 0.0,
+
+            expanded: CellBatch::default(),
+            expanded_backward: CellBatch::default(),
+            heatmap: CellBatch::default(),
+            frontier: CellBatch::default(),
+            highlighted: CellBatch::default(),
+            path: CellBatch::default(),
+        };
+        renderer.reset_view();
+        renderer
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Fit the whole maze into the area again
+    pub fn reset_view(&mut self) {
+        self.cell_size = self.fit_cell_size();
+        self.clamp_view();
+    }
+
+    /// Move the grid into a new area, keeping the zoom and the cell at the centre of the view
+    pub fn resize(&mut self, area: Rect) {
+        let zoom = self.cell_size / self.fit_cell_size();
+        let center = (Vec2::from(self.area.center()) - self.origin) / self.cell_size;
+
+        self.area = area;
+        self.cell_size = self.fit_cell_size() * zoom;
+        self.origin = Vec2::from(area.center()) - center * self.cell_size;
+        self.clamp_view();
+    }
+
+    /// Scale cells by `factor`, keeping the point under the screen position `point` in place
+    pub fn zoom_at(&mut self, point: Vec2, factor: f32) {
+        let fit = self.fit_cell_size();
+        let cell_size = (self.cell_size * factor).clamp(fit, MAX_CELL_SIZE.max(fit));
+
+        self.origin = point - (point - self.origin) * (cell_size / self.cell_size);
+        self.cell_size = cell_size;
+        self.clamp_view();
+    }
+
+    /// Move the grid by a screen offset, e.g. the mouse movement of a drag
+    pub fn pan(&mut self, offset: Vec2) {
+        self.origin += offset;
+        self.clamp_view();
+    }
+
+    /// Restrict drawing to the area, so a zoomed in grid does not cover what is next to it
+    pub fn clip(&self, canvas: &mut Canvas) -> GameResult {
+        if self.area.w >= 1.0 && self.area.h >= 1.0 {
+            canvas.set_scissor_rect(self.area)?;
+        }
+        Ok(())
+    }
+
+    /// Largest cell size the whole grid fits into the area with
+    fn fit_cell_size(&self) -> f32 {
+        // Rows run down the screen and columns across it
+        (self.area.w / self.grid_columns as f32)
+            .min(self.area.h / self.grid_rows as f32)
+            .max(f32::EPSILON)
+    }
+
+    /// Centre the grid along the axes it fits in, and keep the area covered along the others
+    fn clamp_view(&mut self) {
+        let clamp = |origin: f32, start: f32, length: f32, cells: i32| {
+            let grid_length = cells as f32 * self.cell_size;
+            if grid_length <= length {
+                start + (length - grid_length) / 2.0
+            } else {
+                origin.clamp(start + length - grid_length, start)
+            }
+        };
+
+        self.origin = Vec2::new(
+            clamp(self.origin.x, self.area.x, self.area.w, self.grid_columns),
+            clamp(self.origin.y, self.area.y, self.area.h, self.grid_rows),
+        );
+    }
+
+    /// Rows and columns of the cells at least partly inside the area
+    fn visible_cells(&self) -> (Range<i32>, Range<i32>) {
+        let range = |start: f32, length: f32, origin: f32, cells: i32| {
+            let first = ((start - origin) / self.cell_size).floor() as i32;
+            let last = ((start + length - origin) / self.cell_size).ceil() as i32;
+            first.clamp(0, cells)..last.clamp(0, cells)
+        };
+
+        (
+            range(self.area.y, self.area.h, self.origin.y, self.grid_rows),
+            range(self.area.x, self.area.w, self.origin.x, self.grid_columns),
+        )
+    }
+
+    /// Draw the visible maze tiles, rebuilding those whose cells changed since they were last drawn
+    pub fn draw_maze(&mut self, ctx: &GgezContext, canvas: &mut Canvas, maze: &Maze) {
+        let max_cell_cost = maze.max_cell_cost();
+        if max_cell_cost != self.maze_max_cell_cost {
+            self.maze_tiles.clear();
+            self.maze_max_cell_cost = max_cell_cost;
+        }
+
+        let grid_rows = self.grid_rows as usize;
+        let grid_columns = self.grid_columns as usize;
+        let tile_columns = grid_columns.div_ceil(TILE_SIZE);
+        self.maze_tiles
+            .resize_with(grid_rows.div_ceil(TILE_SIZE) * tile_columns, || None);
+
+        let transform = self.grid_transform();
+        let (rows, columns) = self.visible_cells();
+        let tiles = |cells: Range<i32>| {
+            cells.start as usize / TILE_SIZE..(cells.end as usize).div_ceil(TILE_SIZE)
+        };
+        for tile_x in tiles(rows) {
+            for tile_y in tiles(columns.clone()) {
+                let rows = tile_x * TILE_SIZE..((tile_x + 1) * TILE_SIZE).min(grid_rows);
+                let columns = tile_y * TILE_SIZE..((tile_y + 1) * TILE_SIZE).min(grid_columns);
+
+                let tile = &mut self.maze_tiles[tile_x * tile_columns + tile_y];
+                if !tile
+                    .as_ref()
+                    .is_some_and(|tile| tile.is_current(maze, rows.clone(), columns.clone()))
+                {
+                    *tile = Some(MazeTile::new(ctx, maze, rows, columns, max_cell_cost));
+                }
+                if let Some(tile) = tile {
+                    canvas.draw(&tile.instances, transform);
                 }
             }
         }
     }
 
+    fn cell_color(cell: u8, max_cell_cost: f32) -> Color {
+        match cell {
+            CELL_EMPTY => MazeColor::EMPTY.0,
+            CELL_WALL => MazeColor::WALL.0,
+            cost => Self::terrain_color(cost as f32, max_cell_cost),
+        }
+    }
+
+    /// Shade terrain by its cost relative to the most expensive cell in the maze
+    fn terrain_color(cost: f32, max_cell_cost: f32) -> Color {
+        // Keep the cheapest terrain distinguishable from empty cells
+        let t = 0.2 + 0.8 * (cost - 1.0) / (max_cell_cost - 1.0).max(1.0);
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+
+        let empty = MazeColor::EMPTY.0;
+        let terrain = MazeColor::TERRAIN.0;
+        Color::new(
+            lerp(empty.r, terrain.r),
+            lerp(empty.g, terrain.g),
+            lerp(empty.b, terrain.b),
+            1.0,
+        )
+    }
+
+    /// Grid cell under a screen position, inverse of the mapping in `draw_cell`
+    pub fn cell_at(&self, x: f32, y: f32) -> Option<GridPosition> {
+        if !self.area.contains([x, y]) {
+            return None;
+        }
+        let (x, y) = (x - self.origin.x, y - self.origin.y);
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+
+        let position = GridPosition::new((y / self.cell_size) as i32, (x / self.cell_size) as i32);
+        if position.x < self.grid_rows && position.y < self.grid_columns {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
     pub fn draw_cell(&self, canvas: &mut Canvas, position: &GridPosition, color: Color) {
         // Coordinates are reversed: x - y axis, y - x axis
-        let pos_x = (position.y as f32) * self.cell_width;
-        let pos_y = (position.x as f32) * self.cell_height;
+        let pos_x = self.origin.x + (position.y as f32) * self.cell_size;
+        let pos_y = self.origin.y + (position.x as f32) * self.cell_size;
 
-        let rect = Rect::new(pos_x, pos_y, self.cell_width, self.cell_height);
+        let rect = Rect::new(pos_x, pos_y, self.cell_size, self.cell_size);
+        // Skip cells scrolled out of view, solvers of large mazes have plenty of them
+        if !rect.overlaps(&self.area) {
+            return;
+        }
 
         canvas.draw(
             &graphics::Quad,
@@ -55,20 +482,288 @@ impl Renderer {
         );
     }
 
-    pub fn draw_maze_solver(&self, canvas: &mut Canvas, solver: &dyn MazeSolverDrawable) {
-        for cell in solver.expanded_nodes() {
-            self.draw_cell(canvas, &cell, MazeColor::EXPANDED.0);
+    /// Agent moving from `from` to `to`, `t` between 0 and 1 is how far it got.
+    /// Drawn smaller than a cell so agents on neighbouring cells stay apart.
+    pub fn draw_agent(
+        &self,
+        canvas: &mut Canvas,
+        from: &GridPosition,
+        to: &GridPosition,
+        t: f32,
+        color: Color,
+    ) {
+        let lerp = |from: i32, to: i32| from as f32 + (to - from) as f32 * t;
+        let inset = 0.15;
+        let rect = Rect::new(
+            self.origin.x + (lerp(from.y, to.y) + inset) * self.cell_size,
+            self.origin.y + (lerp(from.x, to.x) + inset) * self.cell_size,
+            (1.0 - 2.0 * inset) * self.cell_size,
+            (1.0 - 2.0 * inset) * self.cell_size,
+        );
+
+        canvas.draw(
+            &graphics::Quad,
+            graphics::DrawParam::new().dest_rect(rect).color(color),
+        );
+    }
+
+    /// Straight segments between the centres of consecutive cells
+    pub fn draw_polyline(
+        &self,
+        ctx: &GgezContext,
+        canvas: &mut Canvas,
+        positions: &[GridPosition],
+        color: Color,
+    ) -> GameResult {
+        if positions.len() < 2 {
+            return Ok(());
+        }
+
+        let points = positions
+            .iter()
+            .map(|position| self.cell_center(position))
+            .collect::<Vec<_>>();
+        let width = 0.3 * self.cell_size;
+        let mesh = Mesh::new_line(ctx, &points, width, color)?;
+        canvas.draw(&mesh, graphics::DrawParam::new());
+
+        Ok(())
+    }
+
+    /// Cells of a list that mostly grows between frames, like a path being traced
+    pub fn draw_cells(
+        &mut self,
+        ctx: &GgezContext,
+        canvas: &mut Canvas,
+        cells: &[GridPosition],
+        color: Color,
+    ) {
+        self.path.extend(ctx, cells, color);
+        self.path.draw(canvas, self.grid_transform());
+    }
+
+    pub fn draw_maze_generator(
+        &mut self,
+        ctx: &GgezContext,
+        canvas: &mut Canvas,
+        generator: &MazeGenerator,
+    ) {
+        self.draw_maze(ctx, canvas, generator.maze());
+
+        for cell in generator.active_positions() {
+            self.draw_cell(canvas, &cell, MazeColor::GENERATION_ACTIVE.0);
+        }
+    }
+
+    pub fn draw_maze_solver(
+        &mut self,
+        ctx: &GgezContext,
+        canvas: &mut Canvas,
+        solver: &dyn MazeSolverDrawable,
+        overlays: &Overlays,
+    ) -> GameResult {
+        let unreachable = solver.outcome() == SearchOutcome::Unreachable;
+        let expanded_color = if unreachable {
+            MazeColor::EXPANDED_UNREACHABLE.0
+        } else {
+            MazeColor::EXPANDED.0
+        };
+
+        // Expanded nodes only grow while searching, so just the new ones are added each frame
+        let transform = self.grid_transform();
+        if overlays.heatmap {
+            self.heatmap
+                .set(ctx, Self::heatmap_instances(solver.drawn_expanded_nodes()));
+            self.heatmap.draw(canvas, transform);
+        } else {
+            self.expanded
+                .extend(ctx, solver.drawn_expanded_nodes(), expanded_color);
+            self.expanded.draw(canvas, transform);
+            if !unreachable {
+                self.expanded_backward.extend(
+                    ctx,
+                    solver.backward_expanded_nodes(),
+                    MazeColor::EXPANDED_BACKWARD.0,
+                );
+                self.expanded_backward.draw(canvas, transform);
+            }
+        }
+        if overlays.frontier {
+            self.frontier.set(
+                ctx,
+                solver
+                    .frontier()
+                    .iter()
+                    .map(|cell| Self::cell_instance(cell, MazeColor::TO_BE_EXPANDED.0)),
+            );
+            self.frontier.draw(canvas, transform);
         }
+        self.highlighted.set(
+            ctx,
+            solver
+                .highlighted_nodes()
+                .iter()
+                .map(|cell| Self::cell_instance(cell, MazeColor::HIGHLIGHTED.0)),
+        );
+        self.highlighted.draw(canvas, transform);
 
         // No need to draw path after completion
         // if solver.is_complete() {
         //     for cell in solver.path() {
-        //         self.draw_cell(canvas, &cell, MazeColor::PATH.0);
+        //         self.draw_cell(canvas, cell, MazeColor::PATH.0);
         //     }
         // }
 
         self.draw_cell(canvas, solver.start(), MazeColor::START.0);
 
-        self.draw_cell(canvas, solver.end(), MazeColor::END.0);
+        let end_color = if unreachable {
+            MazeColor::END_UNREACHABLE.0
+        } else {
+            MazeColor::END.0
+        };
+        for goal in solver.goals() {
+            self.draw_cell(canvas, goal, end_color);
+        }
+
+        if overlays.parents {
+            if let Some(path_edges) = solver.path_edges() {
+                self.draw_parent_arrows(ctx, canvas, path_edges)?;
+            }
+        }
+        if overlays.values && self.can_draw_values() {
+            self.draw_cell_values(canvas, solver);
+        }
+
+        Ok(())
+    }
+
+    /// Whether cells are large enough for their g/h/f values
+    pub fn can_draw_values(&self) -> bool {
+        self.cell_size >= MIN_VALUES_CELL_SIZE
+    }
+
+    /// Shade expanded nodes from the first to the last expanded one
+    fn heatmap_instances(expanded_nodes: &[GridPosition]) -> impl Iterator<Item = DrawParam> + '_ {
+        let early = MazeColor::HEATMAP_EARLY.0;
+        let late = MazeColor::HEATMAP_LATE.0;
+        let last = (expanded_nodes.len().max(2) - 1) as f32;
+
+        expanded_nodes.iter().enumerate().map(move |(order, cell)| {
+            let t = order as f32 / last;
+            let lerp = |from: f32, to: f32| from + (to - from) * t;
+            let color = Color::new(
+                lerp(early.r, late.r),
+                lerp(early.g, late.g),
+                lerp(early.b, late.b),
+                lerp(early.a, late.a),
+            );
+            Self::cell_instance(cell, color)
+        })
+    }
+
+    /// Arrow from the centre of every cell with a parent towards it, at most 0.6 cells long
+    fn draw_parent_arrows(
+        &self,
+        ctx: &GgezContext,
+        canvas: &mut Canvas,
+        path_edges: &MazePathEdges,
+    ) -> GameResult {
+        let cell_size = self.cell_size;
+        let width = (0.06 * cell_size).max(1.0);
+
+        let mut builder = MeshBuilder::new();
+        let mut arrows = 0;
+        let (rows, columns) = self.visible_cells();
+        for x in rows {
+            for y in columns.clone() {
+                let position = GridPosition::new(x, y);
+                let Some(parent) = path_edges.parent(&position) else {
+                    continue;
+                };
+
+                let from = self.cell_center(&position);
+                let offset = self.cell_center(&parent) - from;
+                if offset == Vec2::ZERO {
+                    continue;
+                }
+                let direction = offset.normalize();
+                let tip = from + direction * offset.length().min(0.6 * cell_size);
+
+                // Head made of 2 short lines angled back from the tip
+                let head = 0.2 * cell_size;
+                let left = tip - Vec2::from_angle(0.5).rotate(direction) * head;
+                let right = tip - Vec2::from_angle(-0.5).rotate(direction) * head;
+
+                let color = MazeColor::PARENT_ARROW.0;
+                builder.line(&[from, tip], width, color)?;
+                builder.line(&[left, tip, right], width, color)?;
+                arrows += 1;
+            }
+        }
+
+        if arrows > 0 {
+            let mesh = Mesh::from_data(ctx, builder.build());
+            canvas.draw(&mesh, graphics::DrawParam::new());
+        }
+        Ok(())
+    }
+
+    fn draw_cell_values(&self, canvas: &mut Canvas, solver: &dyn MazeSolverDrawable) {
+        let format = |value: f32| {
+            if value.fract() == 0.0 {
+                format!("{}", value)
+            } else {
+                format!("{:.1}", value)
+            }
+        };
+
+        let (rows, columns) = self.visible_cells();
+        for x in rows {
+            for y in columns.clone() {
+                let position = GridPosition::new(x, y);
+                let Some(values) = solver.cell_values(&position) else {
+                    continue;
+                };
+
+                let mut text = Text::new(format!(
+                    "g {}\nh {}\nf {}",
+                    format(values.g),
+                    format(values.h),
+                    format(values.f)
+                ));
+                text.set_scale(0.28 * self.cell_size);
+                let dest =
+                    self.origin + Vec2::new(y as f32 + 0.05, x as f32 + 0.05) * self.cell_size;
+                canvas.draw(
+                    &text,
+                    graphics::DrawParam::new()
+                        .dest(dest)
+                        .color(MazeColor::CELL_VALUES.0),
+                );
+            }
+        }
+    }
+
+    /// Maps grid coordinates of batched instances, 1 unit per cell, to the screen
+    fn grid_transform(&self) -> DrawParam {
+        DrawParam::new()
+            .dest(self.origin)
+            .scale(Vec2::splat(self.cell_size))
+    }
+
+    /// Batch instance of a cell in grid coordinates
+    fn cell_instance(position: &GridPosition, color: Color) -> DrawParam {
+        // Coordinates are reversed: x - y axis, y - x axis
+        DrawParam::new()
+            .dest(Vec2::new(position.y as f32, position.x as f32))
+            .color(color)
+    }
+
+    fn cell_center(&self, position: &GridPosition) -> Vec2 {
+        self.origin
+            + Vec2::new(
+                (position.y as f32 + 0.5) * self.cell_size,
+                (position.x as f32 + 0.5) * self.cell_size,
+            )
     }
 }
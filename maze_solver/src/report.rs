@@ -0,0 +1,229 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::{mapf::*, maze::*, solver::*};
+
+/// Machine readable result of running a solver to completion
+#[derive(Serialize, Clone, Debug)]
+pub struct SolverReport {
+    pub algorithm: String,
+    pub outcome: SearchOutcome,
+    pub path: Vec<GridPosition>,
+    /// None unless a path was found
+    pub path_cost: Option<f32>,
+    /// Guaranteed bound on path_cost / cheapest path cost, for bounded suboptimal searches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suboptimality_bound: Option<f32>,
+    pub expanded_nodes: usize,
+    /// Cells looked at without being expanded, for JPS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scanned_cells: Option<usize>,
+    /// Bounds of the iterations of iterative deepening searches
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub thresholds: Vec<f32>,
+    /// Wall time in milliseconds
+    pub wall_time_ms: f64,
+}
+
+impl SolverReport {
+    pub fn new(solver: &dyn MazeSolver, wall_time: Duration) -> Self {
+        Self {
+            algorithm: solver.name().to_string(),
+            outcome: solver.outcome(),
+            path: solver.path().clone(),
+            path_cost: solver.is_complete().then(|| solver.path_cost()),
+            suboptimality_bound: solver.suboptimality_bound(),
+            expanded_nodes: solver.expanded_count(),
+            scanned_cells: solver.scanned_cells(),
+            thresholds: solver.thresholds().to_vec(),
+            wall_time_ms: wall_time.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Run a solver to completion and measure it, aborting once it expanded more than `max_expansions` nodes
+pub fn run_with_report(solver: &mut dyn MazeSolver, max_expansions: Option<usize>) -> SolverReport {
+    let time_start = Instant::now();
+    match max_expansions {
+        Some(max_expansions) => {
+            while solver.step() {
+                if solver.expanded_count() > max_expansions {
+                    solver.abort();
+                }
+            }
+        }
+        None => solver.run(),
+    }
+    SolverReport::new(solver, time_start.elapsed())
+}
+
+/// Timed path of 1 agent in a multi-agent report
+#[derive(Serialize, Clone, Debug)]
+pub struct AgentReport {
+    pub start: GridPosition,
+    pub goal: GridPosition,
+    /// Cell at every time step, empty unless paths were found
+    pub path: Vec<GridPosition>,
+    /// Time steps until the agent rests at its goal, None unless paths were found
+    pub path_cost: Option<usize>,
+}
+
+/// Machine readable result of running a multi-agent solver to completion
+#[derive(Serialize, Clone, Debug)]
+pub struct MapfReport {
+    pub algorithm: String,
+    pub outcome: SearchOutcome,
+    /// None unless paths were found
+    pub sum_of_costs: Option<usize>,
+    pub makespan: Option<usize>,
+    /// Low-level (cell, time step) expansions
+    pub expanded_nodes: usize,
+    /// Constraint tree nodes, for CBS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high_level_nodes: Option<usize>,
+    /// Wall time in milliseconds
+    pub wall_time_ms: f64,
+    pub agents: Vec<AgentReport>,
+}
+
+impl MapfReport {
+    pub fn new(solver: &MapfSolver, wall_time: Duration) -> Self {
+        let found = solver.outcome() == SearchOutcome::Found;
+        let agents = solver
+            .agents()
+            .iter()
+            .enumerate()
+            .map(|(index, agent)| {
+                let path = solver.paths().get(index).cloned().unwrap_or_default();
+                AgentReport {
+                    start: agent.start,
+                    goal: agent.goal,
+                    path_cost: found.then(|| path.len() - 1),
+                    path,
+                }
+            })
+            .collect();
+
+        Self {
+            algorithm: solver.name().to_string(),
+            outcome: solver.outcome(),
+            sum_of_costs: found.then(|| solver.sum_of_costs()),
+            makespan: found.then(|| solver.makespan()),
+            expanded_nodes: solver.expanded_nodes(),
+            high_level_nodes: (solver.algorithm() == MapfAlgorithm::ConflictBasedSearch)
+                .then(|| solver.high_level_nodes()),
+            wall_time_ms: wall_time.as_secs_f64() * 1000.0,
+            agents,
+        }
+    }
+}
+
+/// Run a multi-agent solver to completion and measure it
+pub fn run_mapf_with_report(solver: &mut MapfSolver) -> MapfReport {
+    let time_start = Instant::now();
+    solver.run();
+    MapfReport::new(solver, time_start.elapsed())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(anyhow::anyhow!("Unknown report format: {}", s)),
+        }
+    }
+}
+
+/// Path as space separated "x;y" pairs to keep it in a single CSV column
+fn format_csv_path(path: &[GridPosition]) -> String {
+    path.iter()
+        .map(|position| format!("{};{}", position.x, position.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn format_reports(reports: &[SolverReport], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Json => serde_json::to_string_pretty(reports).unwrap(),
+        ReportFormat::Csv => {
+            let mut csv = String::from(
+                "algorithm,outcome,path_cost,suboptimality_bound,expanded_nodes,scanned_cells,wall_time_ms,thresholds,path\n",
+            );
+            for report in reports {
+                let path = format_csv_path(&report.path);
+                let thresholds = report
+                    .thresholds
+                    .iter()
+                    .map(|threshold| threshold.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                csv += format!(
+                    "{},{},{},{},{},{},{:.3},{},{}\n",
+                    report.algorithm,
+                    report.outcome,
+                    report
+                        .path_cost
+                        .map(|cost| cost.to_string())
+                        .unwrap_or_default(),
+                    report
+                        .suboptimality_bound
+                        .map(|bound| bound.to_string())
+                        .unwrap_or_default(),
+                    report.expanded_nodes,
+                    report
+                        .scanned_cells
+                        .map(|scanned_cells| scanned_cells.to_string())
+                        .unwrap_or_default(),
+                    report.wall_time_ms,
+                    thresholds,
+                    path
+                )
+                .as_str();
+            }
+            csv
+        }
+    }
+}
+
+/// CSV has a row per agent, repeating the totals of its search
+pub fn format_mapf_reports(reports: &[MapfReport], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Json => serde_json::to_string_pretty(reports).unwrap(),
+        ReportFormat::Csv => {
+            let mut csv = String::from(
+                "algorithm,outcome,sum_of_costs,makespan,expanded_nodes,high_level_nodes,wall_time_ms,agent,path_cost,path\n",
+            );
+            let optional =
+                |value: Option<usize>| value.map(|value| value.to_string()).unwrap_or_default();
+            for report in reports {
+                for (index, agent) in report.agents.iter().enumerate() {
+                    csv += format!(
+                        "{},{},{},{},{},{},{:.3},{},{},{}\n",
+                        report.algorithm,
+                        report.outcome,
+                        optional(report.sum_of_costs),
+                        optional(report.makespan),
+                        report.expanded_nodes,
+                        optional(report.high_level_nodes),
+                        report.wall_time_ms,
+                        index,
+                        optional(agent.path_cost),
+                        format_csv_path(&agent.path)
+                    )
+                    .as_str();
+                }
+            }
+            csv
+        }
+    }
+}
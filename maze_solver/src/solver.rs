@@ -0,0 +1,401 @@
+use serde::Serialize;
+
+use crate::{
+    astar::*, beam::*, bfs::*, bidirectional_astar::*, bidirectional_bfs::*, dfs::*, dijkstra::*,
+    dstar_lite::*, greedy::*, heuristic::*, idastar::*, iddfs::*, jps::*, lpastar::*, maze::*,
+    theta::*,
+};
+
+/// Heuristic weight of weighted A* unless configured
+pub const DEFAULT_WEIGHT: f32 = 2.0;
+/// Beam search width unless configured
+pub const DEFAULT_BEAM_WIDTH: usize = 16;
+
+/// Tuning shared by the algorithms that support it
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SolverOptions {
+    /// Heuristic for informed searches, defaults to the tightest admissible one for the maze connectivity
+    pub heuristic: Option<Heuristic>,
+    /// Heuristic weight ε of weighted A*, defaults to `DEFAULT_WEIGHT`
+    pub weight: Option<f32>,
+    /// Nodes kept per layer by beam search, defaults to `DEFAULT_BEAM_WIDTH`
+    pub beam_width: Option<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MazeSolveAlgorithm {
+    Bfs,
+    Dfs,
+    Dijkstra,
+    AStar,
+    BidirectionalBfs,
+    BidirectionalAStar,
+    Iddfs,
+    IdaStar,
+    GreedyBestFirst,
+    WeightedAStar,
+    Beam,
+    Jps,
+    ThetaStar,
+    LazyThetaStar,
+    LpaStar,
+    DStarLite,
+}
+
+impl MazeSolveAlgorithm {
+    pub const ALL: [Self; 16] = [
+        Self::Bfs,
+        Self::Dfs,
+        Self::Dijkstra,
+        Self::AStar,
+        Self::BidirectionalBfs,
+        Self::BidirectionalAStar,
+        Self::Iddfs,
+        Self::IdaStar,
+        Self::GreedyBestFirst,
+        Self::WeightedAStar,
+        Self::Beam,
+        Self::Jps,
+        Self::ThetaStar,
+        Self::LazyThetaStar,
+        Self::LpaStar,
+        Self::DStarLite,
+    ];
+
+    pub fn new_solver(
+        &self,
+        maze: Maze,
+        position_start: GridPosition,
+        position_end: GridPosition,
+        options: &SolverOptions,
+    ) -> Box<dyn MazeSolverDrawable> {
+        let heuristic = options
+            .heuristic
+            .unwrap_or_else(|| Heuristic::default_for(maze.connectivity));
+
+        match self {
+            Self::Bfs => Box::new(MazeSolverBFS::new(maze, position_start, position_end)),
+            Self::Dfs => Box::new(MazeSolverDFS::new(maze, position_start, position_end)),
+            Self::Dijkstra => Box::new(MazeSolverDijkstra::new(maze, position_start, position_end)),
+            Self::AStar => Box::new(
+                MazeSolverAStar::new(maze, position_start, position_end).with_heuristic(heuristic),
+            ),
+            Self::BidirectionalBfs => Box::new(MazeSolverBidirectionalBFS::new(
+                maze,
+                position_start,
+                position_end,
+            )),
+            Self::BidirectionalAStar => Box::new(
+                MazeSolverBidirectionalAStar::new(maze, position_start, position_end)
+                    .with_heuristic(heuristic),
+            ),
+            Self::Iddfs => Box::new(MazeSolverIDDFS::new(maze, position_start, position_end)),
+            Self::IdaStar => Box::new(
+                MazeSolverIDAStar::new(maze, position_start, position_end)
+                    .with_heuristic(heuristic),
+            ),
+            Self::GreedyBestFirst => Box::new(
+                MazeSolverGreedyBestFirst::new(maze, position_start, position_end)
+                    .with_heuristic(heuristic),
+            ),
+            Self::WeightedAStar => Box::new(
+                MazeSolverAStar::new(maze, position_start, position_end)
+                    .with_heuristic(heuristic)
+                    .with_weight(options.weight.unwrap_or(DEFAULT_WEIGHT)),
+            ),
+            Self::Beam => Box::new(
+                MazeSolverBeam::new(
+                    maze,
+                    position_start,
+                    position_end,
+                    options.beam_width.unwrap_or(DEFAULT_BEAM_WIDTH),
+                )
+                .with_heuristic(heuristic),
+            ),
+            Self::Jps => Box::new(
+                MazeSolverJPS::new(maze, position_start, position_end).with_heuristic(heuristic),
+            ),
+            // Only an explicitly chosen heuristic replaces the Euclidean default of any-angle search
+            Self::ThetaStar | Self::LazyThetaStar => {
+                let mut solver = MazeSolverThetaStar::new(maze, position_start, position_end);
+                if let Some(heuristic) = options.heuristic {
+                    solver = solver.with_heuristic(heuristic);
+                }
+                if *self == Self::LazyThetaStar {
+                    solver = solver.lazy();
+                }
+                Box::new(solver)
+            }
+            Self::LpaStar => Box::new(
+                MazeSolverLPAStar::new(maze, position_start, position_end)
+                    .with_heuristic(heuristic),
+            ),
+            Self::DStarLite => Box::new(
+                MazeSolverDStarLite::new(maze, position_start, position_end)
+                    .with_heuristic(heuristic),
+            ),
+        }
+    }
+}
+
+impl MazeSolveAlgorithm {
+    /// Solver reaching whichever of the goals is nearest, None for algorithms that only search for a single goal.
+    /// Panics if `goals` is empty.
+    pub fn new_nearest_goal_solver(
+        &self,
+        maze: Maze,
+        position_start: GridPosition,
+        goals: Vec<GridPosition>,
+        options: &SolverOptions,
+    ) -> Option<Box<dyn MazeSolverDrawable>> {
+        let heuristic = options
+            .heuristic
+            .unwrap_or_else(|| Heuristic::default_for(maze.connectivity));
+        assert!(
+            !goals.is_empty(),
+            "Nearest goal search needs at least 1 goal"
+        );
+        let position_end = goals[0];
+
+        match self {
+            Self::Bfs => Some(Box::new(
+                MazeSolverBFS::new(maze, position_start, position_end).with_goals(goals),
+            )),
+            Self::Dijkstra => Some(Box::new(
+                MazeSolverDijkstra::new(maze, position_start, position_end).with_goals(goals),
+            )),
+            Self::AStar => Some(Box::new(
+                MazeSolverAStar::new(maze, position_start, position_end)
+                    .with_heuristic(heuristic)
+                    .with_goals(goals),
+            )),
+            Self::WeightedAStar => Some(Box::new(
+                MazeSolverAStar::new(maze, position_start, position_end)
+                    .with_heuristic(heuristic)
+                    .with_weight(options.weight.unwrap_or(DEFAULT_WEIGHT))
+                    .with_goals(goals),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for MazeSolveAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "bfs" => Ok(Self::Bfs),
+            "dfs" => Ok(Self::Dfs),
+            "dijkstra" | "ucs" => Ok(Self::Dijkstra),
+            "astar" | "a*" => Ok(Self::AStar),
+            "bibfs" | "bidirectional-bfs" => Ok(Self::BidirectionalBfs),
+            "biastar" | "bidirectional-astar" => Ok(Self::BidirectionalAStar),
+            "iddfs" => Ok(Self::Iddfs),
+            "idastar" | "ida*" => Ok(Self::IdaStar),
+            "greedy" | "best-first" => Ok(Self::GreedyBestFirst),
+            "wastar" | "weighted-astar" => Ok(Self::WeightedAStar),
+            "beam" => Ok(Self::Beam),
+            "jps" | "jump-point" => Ok(Self::Jps),
+            "theta" | "theta*" => Ok(Self::ThetaStar),
+            "lazy-theta" | "lazy-theta*" => Ok(Self::LazyThetaStar),
+            "lpastar" | "lpa*" => Ok(Self::LpaStar),
+            "dstar-lite" | "d*lite" => Ok(Self::DStarLite),
+            _ => Err(anyhow::anyhow!("Unknown algorithm: {}", s)),
+        }
+    }
+}
+
+/// State of a search
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchOutcome {
+    InProgress,
+    /// A path to the goal was found
+    Found,
+    /// The reachable search space was exhausted without reaching the goal
+    Unreachable,
+    /// Stopped before finishing, e.g. after hitting an expansion limit
+    Aborted,
+}
+
+impl std::fmt::Display for SearchOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Self::InProgress => "in_progress",
+            Self::Found => "found",
+            Self::Unreachable => "unreachable",
+            Self::Aborted => "aborted",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Rendering agnostic interface shared by all maze solvers
+pub trait MazeSolver {
+    /// Human readable algorithm name
+    fn name(&self) -> &'static str;
+
+    /// 1 step of search, returns false once the search is no longer in progress
+    fn step(&mut self) -> bool;
+
+    /// Run the search until the path is found or the goal turns out to be unreachable
+    fn run(&mut self) {
+        while self.step() {}
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition>;
+
+    /// Number of expansions so far, iterative deepening searches count more than `expanded_nodes` keeps
+    fn expanded_count(&self) -> usize {
+        self.expanded_nodes().len()
+    }
+
+    /// Cells looked at without being expanded, for searches that skip over cells like JPS
+    fn scanned_cells(&self) -> Option<usize> {
+        None
+    }
+
+    /// Nodes expanded by a search running backwards from the goal, also included in `expanded_nodes`
+    fn backward_expanded_nodes(&self) -> &[GridPosition] {
+        &[]
+    }
+
+    /// Cost bounds of the iterations so far, for iterative deepening searches
+    fn thresholds(&self) -> &[f32] {
+        &[]
+    }
+
+    /// For bounded suboptimal searches, the factor by which the found path may exceed the cheapest one
+    fn suboptimality_bound(&self) -> Option<f32> {
+        None
+    }
+
+    fn start(&self) -> &GridPosition;
+    fn end(&self) -> &GridPosition;
+
+    /// Every goal of the search, multi-goal searches reach the nearest or visit all of them
+    fn goals(&self) -> &[GridPosition] {
+        std::slice::from_ref(self.end())
+    }
+
+    fn outcome(&self) -> SearchOutcome;
+
+    /// Stop a search that is still in progress
+    fn abort(&mut self);
+
+    /// Whether a path was found
+    fn is_complete(&self) -> bool {
+        self.outcome() == SearchOutcome::Found
+    }
+
+    /// Sum of the traversal costs along the complete path
+    fn path_cost(&self) -> f32;
+
+    /// Complete start -> end path, empty unless a path was found
+    fn path(&self) -> &Vec<GridPosition>;
+
+    /// Whether `path` holds any-angle waypoints joined by straight segments rather than adjacent cells
+    fn has_waypoint_path(&self) -> bool {
+        false
+    }
+
+    /// Apply a change of a maze cell, such as a wall being added or removed, to the search.
+    /// Returns false if the solver cannot repair its search and has to be restarted on the changed maze.
+    fn set_cell(&mut self, _position: &GridPosition, _value: u8) -> bool {
+        false
+    }
+
+    /// Move the start position, returns false if the solver has to be restarted from the new start
+    fn set_start(&mut self, _position: &GridPosition) -> bool {
+        false
+    }
+}
+
+/// Search values of a reached cell, shown by the viewer
+#[derive(Clone, Copy, Debug)]
+pub struct CellValues {
+    /// Best known cost from the start, or from the goal for searches running backwards
+    pub g: f32,
+    /// Heuristic estimate of the remaining cost
+    pub h: f32,
+    /// Priority in the open list
+    pub f: f32,
+}
+
+/// Solver that can be animated by the viewer
+pub trait MazeSolverDrawable: MazeSolver {
+    /// Advance the search by a solver specific number of steps for one frame
+    fn update(&mut self);
+
+    /// Expanded nodes to draw
+    fn drawn_expanded_nodes(&self) -> &[GridPosition] {
+        self.expanded_nodes()
+    }
+
+    /// Nodes drawn on top of the expanded ones, such as the jump points of JPS
+    fn highlighted_nodes(&self) -> &[GridPosition] {
+        &[]
+    }
+
+    /// Cells waiting to be expanded
+    fn frontier(&self) -> Vec<GridPosition> {
+        Vec::new()
+    }
+
+    /// Number of cells waiting to be expanded, drawn every frame so solvers override it to skip building the frontier
+    fn frontier_len(&self) -> usize {
+        self.frontier().len()
+    }
+
+    /// Search values of a cell, None for cells not reached yet or solvers without values
+    fn cell_values(&self, _position: &GridPosition) -> Option<CellValues> {
+        None
+    }
+
+    /// Parent of every reached cell, for solvers keeping a search tree
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        None
+    }
+}
+
+/// Log the result of a finished search
+pub fn print_result_path(solver: &dyn MazeSolver) {
+    log::info!(
+        "Pathfinding result for {} search from ({},{}) to ({},{}) - {:?}",
+        solver.name(),
+        solver.start().x,
+        solver.start().y,
+        solver.end().x,
+        solver.end().y,
+        solver.outcome()
+    );
+
+    if solver.outcome() != SearchOutcome::Found {
+        match solver.outcome() {
+            SearchOutcome::Unreachable => log::info!("No path, the goal is unreachable"),
+            _ => log::info!("Search stopped before finding a path"),
+        }
+        print_expanded_count(solver);
+        return;
+    }
+
+    let mut path_positions = String::from("Complete path: ");
+    for position in solver.path() {
+        path_positions += format!(" ({}, {})", position.x, position.y).as_str();
+    }
+    log::info!("{}", path_positions);
+
+    log::info!("Path cost: {}", solver.path_cost());
+    if let Some(bound) = solver.suboptimality_bound() {
+        log::info!("Path cost is at most {} times the cheapest path", bound);
+    }
+    print_expanded_count(solver);
+}
+
+fn print_expanded_count(solver: &dyn MazeSolver) {
+    log::info!("Number of explored nodes: {}", solver.expanded_count());
+    if let Some(scanned_cells) = solver.scanned_cells() {
+        log::info!("Number of scanned cells: {}", scanned_cells);
+    }
+}
@@ -0,0 +1,315 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    astar::{open_count, open_positions, SearchNode},
+    heuristic::*,
+    maze::*,
+    solver::*,
+};
+
+const STEPS_PER_UPDATE: usize = 3;
+
+/// Theta*, any-angle A* where a node takes its parent's parent as its own whenever that cell is in line of sight.
+/// Paths are waypoints joined by straight segments and cost their Euclidean length.
+pub struct MazeSolverThetaStar {
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+
+    heuristic: Heuristic,
+    /// Lazy Theta* assumes line of sight when generating a node and only checks it on expansion
+    lazy: bool,
+
+    /// Best known g(n) values
+    search_grid: Vec<Vec<f32>>,
+
+    /// True once a cell has been expanded
+    closed_grid: Vec<Vec<bool>>,
+
+    /// Open list of nodes to visit
+    current_search_positions: BinaryHeap<Reverse<SearchNode>>,
+
+    /// Parents are any cell in line of sight rather than a neighbour
+    path_edges: MazePathEdges,
+
+    outcome: SearchOutcome,
+    complete_path: Vec<GridPosition>,
+
+    expanded_nodes: Vec<GridPosition>,
+}
+
+impl MazeSolverThetaStar {
+    pub fn new(maze: Maze, position_start: GridPosition, position_end: GridPosition) -> Self {
+        if maze.max_cell_cost() > 1.0 {
+            log::warn!(
+                "Any-angle search treats terrain as empty cells, the path may not be the cheapest"
+            );
+        }
+
+        let mut search_grid = vec![vec![f32::MAX; maze.grid[0].len()]; maze.grid.len()];
+        search_grid[position_start.x as usize][position_start.y as usize] = 0.0;
+
+        let closed_grid = vec![vec![false; maze.grid[0].len()]; maze.grid.len()];
+
+        // Any-angle paths can be shorter than octile or Manhattan distances
+        let heuristic = Heuristic::Euclidean;
+        let h = heuristic.distance(&position_start, &position_end);
+
+        let mut current_search_positions = BinaryHeap::new();
+        current_search_positions.push(Reverse(SearchNode::new(0.0, h, h, position_start)));
+
+        let path_edges = MazePathEdges::new_with_maze(&maze);
+
+        Self {
+            maze,
+            position_start,
+            position_end,
+            heuristic,
+            lazy: false,
+            search_grid,
+            closed_grid,
+
+            current_search_positions,
+            path_edges,
+
+            outcome: SearchOutcome::InProgress,
+            complete_path: Vec::new(),
+
+            expanded_nodes: Vec::new(),
+        }
+    }
+
+    /// Defer line of sight checks until a node is expanded, which is when most of them turn out unnecessary
+    pub fn lazy(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        if !matches!(heuristic, Heuristic::Euclidean | Heuristic::Zero) {
+            log::warn!(
+                "{:?} heuristic can overestimate any-angle distances, {} may not find the shortest path",
+                heuristic,
+                self.name()
+            );
+        }
+        self.heuristic = heuristic;
+        let h = self.h(&self.position_start);
+        self.current_search_positions.clear();
+        self.current_search_positions.push(Reverse(SearchNode::new(
+            0.0,
+            h,
+            h,
+            self.position_start,
+        )));
+        self
+    }
+
+    /// 1 step of Theta* search, expanding the open node with the lowest f by 1
+    fn search(&mut self) {
+        if self.outcome != SearchOutcome::InProgress {
+            return;
+        }
+
+        let Some(start) = self.current_search_positions.pop().map(|node| node.0) else {
+            self.outcome = SearchOutcome::Unreachable;
+            print_result_path(self);
+            return;
+        };
+
+        // A cell is pushed again whenever a cheaper path to it is found, skip the superseded entries
+        if start.g > self.g(&start.position)
+            || self.closed_grid[start.position.x as usize][start.position.y as usize]
+        {
+            return;
+        }
+
+        // Lazy Theta* only now checks the assumed parent, a cell that got more expensive waits for its turn again
+        if self.lazy {
+            let g = self.set_vertex(&start.position);
+            if g > start.g {
+                let h = self.h(&start.position);
+                self.current_search_positions.push(Reverse(SearchNode::new(
+                    g,
+                    h,
+                    g + h,
+                    start.position,
+                )));
+                return;
+            }
+        }
+
+        self.closed_grid[start.position.x as usize][start.position.y as usize] = true;
+        self.expanded_nodes.push(start.position);
+
+        if start.position == self.position_end {
+            self.outcome = SearchOutcome::Found;
+            self.complete_path = self
+                .path_edges
+                .build_path(&self.position_start, &self.position_end)
+                .unwrap();
+            print_result_path(self);
+            return;
+        }
+
+        let parent = self.parent(&start.position);
+        let g_start = self.g(&start.position);
+        for cell in self.maze.neighbours(&start.position) {
+            if self.closed_grid[cell.x as usize][cell.y as usize] {
+                continue;
+            }
+
+            // Connect straight to the parent's parent when possible, Lazy Theta* verifies that on expansion
+            let (source, g) = if self.lazy || self.maze.has_line_of_sight(&parent, &cell) {
+                (parent, self.g(&parent) + Self::euclidean(&parent, &cell))
+            } else {
+                (
+                    start.position,
+                    g_start + Self::euclidean(&start.position, &cell),
+                )
+            };
+
+            if g < self.g(&cell) {
+                self.search_grid[cell.x as usize][cell.y as usize] = g;
+                self.path_edges.add_edge(&source, &cell);
+
+                let h = self.h(&cell);
+                self.current_search_positions
+                    .push(Reverse(SearchNode::new(g, h, g + h, cell)));
+            }
+        }
+    }
+
+    /// Lazy Theta*: if the assumed parent is not in line of sight, fall back to the best expanded neighbour.
+    /// Returns the resulting g.
+    fn set_vertex(&mut self, position: &GridPosition) -> f32 {
+        let parent = self.parent(position);
+        if self.maze.has_line_of_sight(&parent, position) {
+            return self.g(position);
+        }
+
+        let best = self
+            .maze
+            .neighbours(position)
+            .filter(|cell| self.closed_grid[cell.x as usize][cell.y as usize])
+            .map(|cell| (cell, self.g(&cell) + Self::euclidean(&cell, position)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((cell, g)) = best {
+            self.search_grid[position.x as usize][position.y as usize] = g;
+            self.path_edges.add_edge(&cell, position);
+        }
+        self.g(position)
+    }
+
+    fn parent(&self, position: &GridPosition) -> GridPosition {
+        if *position == self.position_start {
+            self.position_start
+        } else {
+            self.path_edges.edges[position.x as usize][position.y as usize]
+        }
+    }
+
+    fn g(&self, position: &GridPosition) -> f32 {
+        self.search_grid[position.x as usize][position.y as usize]
+    }
+
+    /// Heuristic function - distance from end position to position parameter
+    fn h(&self, position: &GridPosition) -> f32 {
+        self.heuristic.distance(position, &self.position_end)
+    }
+
+    fn euclidean(from: &GridPosition, to: &GridPosition) -> f32 {
+        Heuristic::Euclidean.distance(from, to)
+    }
+}
+
+impl MazeSolver for MazeSolverThetaStar {
+    fn name(&self) -> &'static str {
+        if self.lazy {
+            "Lazy Theta*"
+        } else {
+            "Theta*"
+        }
+    }
+
+    fn step(&mut self) -> bool {
+        self.search();
+        self.outcome == SearchOutcome::InProgress
+    }
+
+    fn expanded_nodes(&self) -> &Vec<GridPosition> {
+        &self.expanded_nodes
+    }
+
+    fn start(&self) -> &GridPosition {
+        &self.position_start
+    }
+
+    fn end(&self) -> &GridPosition {
+        &self.position_end
+    }
+
+    fn outcome(&self) -> SearchOutcome {
+        self.outcome
+    }
+
+    fn abort(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            self.outcome = SearchOutcome::Aborted;
+            print_result_path(self);
+        }
+    }
+
+    /// Euclidean length of the waypoint path
+    fn path_cost(&self) -> f32 {
+        self.complete_path
+            .windows(2)
+            .map(|segment| Self::euclidean(&segment[0], &segment[1]))
+            .sum()
+    }
+
+    fn path(&self) -> &Vec<GridPosition> {
+        &self.complete_path
+    }
+
+    fn has_waypoint_path(&self) -> bool {
+        true
+    }
+}
+
+impl MazeSolverDrawable for MazeSolverThetaStar {
+    fn update(&mut self) {
+        if self.outcome == SearchOutcome::InProgress {
+            for _ in 0..STEPS_PER_UPDATE {
+                self.search();
+            }
+        }
+    }
+
+    fn frontier(&self) -> Vec<GridPosition> {
+        open_positions(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn frontier_len(&self) -> usize {
+        open_count(
+            &self.current_search_positions,
+            &self.search_grid,
+            &self.closed_grid,
+        )
+    }
+
+    fn cell_values(&self, position: &GridPosition) -> Option<CellValues> {
+        let g = self.g(position);
+        let h = self.h(position);
+        (g != f32::MAX).then_some(CellValues { g, h, f: g + h })
+    }
+
+    /// Parents can be any cell in line of sight
+    fn path_edges(&self) -> Option<&MazePathEdges> {
+        Some(&self.path_edges)
+    }
+}
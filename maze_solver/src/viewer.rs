@@ -0,0 +1,1390 @@
+use ggez::{
+    event::{self, EventHandler},
+    glam::Vec2,
+    graphics::{Canvas, Color, DrawParam, Quad, Rect, Text},
+    input::{
+        keyboard::{KeyCode, KeyInput, KeyMods},
+        mouse::MouseButton,
+    },
+    Context as GgezContext, GameResult,
+};
+
+use crate::{generator::*, heuristic::*, mapf::*, maze::*, multi_goal::*, renderer::*, solver::*};
+
+const TARGET_FPS: u32 = 30;
+
+/// Generation is spread over roughly this many updates regardless of the maze size
+const GENERATION_UPDATES: usize = 150;
+
+/// Solver updates per frame the speed can be set to, 1 by default
+const SPEEDS: [f32; 9] = [0.125, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0];
+const DEFAULT_SPEED: usize = 3;
+
+/// Solver steps each comparison panel takes per update at speed 1
+const COMPARISON_STEPS_PER_UPDATE: f32 = 3.0;
+/// Height of the counters below each comparison panel
+const COMPARISON_STATS_HEIGHT: f32 = 56.0;
+const COMPARISON_MARGIN: f32 = 6.0;
+
+/// Agents planned by Cooperative A* or constraint tree nodes expanded by CBS per update
+const MAPF_STEPS_PER_UPDATE: usize = 10;
+
+/// Time steps multi-agent paths advance per second
+const AGENT_MOVES_PER_SECOND: f32 = 4.0;
+
+/// Cell size factor of 1 mouse wheel notch
+const ZOOM_STEP: f32 = 1.2;
+
+const HUD_TEXT_SCALE: f32 = 18.0;
+const HUD_MARGIN: f32 = 8.0;
+const HUD_PADDING: f32 = 6.0;
+/// Translucent so the maze stays visible under the HUD
+const HUD_BACKGROUND: Color = Color::new(1.0, 1.0, 1.0, 0.8);
+
+const KEY_HELP: &str = "\
+A / B / D / J - A*, BFS, DFS, Dijkstra
+Shift + A / B / D - Bidirectional A*, bidirectional BFS, IDDFS
+I / E / W / K / P - IDA*, greedy, weighted A*, beam, JPS
+Y / Shift + Y - Theta*, Lazy Theta*
+L / Shift + L - LPA*, D* Lite
+T - Trace path
+C - Cycle connectivity
+H - Cycle heuristic
+M - Nearest goal or visit all goals
+G / N - Generate maze, next generation algorithm
+Space - Pause or resume
+Right / Left - Step, rewind (Shift + Left - 10)
+Up / Down - Faster, slower
+R - Restart search
+1 / 2 / 3 / 4 - Frontier, values, heatmap, parents
+O - Compare solvers side by side
+0 - Fit maze to window
+F1 - Key help, hide HUD
+Left drag - Move start or goal, paint walls
+Right drag - Erase walls
+Shift + left click - Add or remove goal
+Wheel - Zoom
+Middle or Ctrl + left drag - Pan";
+
+/// What the HUD shows, F1 cycles through them
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Hud {
+    Status,
+    /// Status and the key bindings
+    Help,
+    Hidden,
+}
+
+/// What a mouse drag currently edits
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MouseEdit {
+    None,
+    MoveStart,
+    MoveEnd,
+    PaintWall,
+    EraseWall,
+    /// Dragging the view of a zoomed in maze
+    Pan,
+}
+
+/// What was applied to the solver since it was created, replayed on a new solver to rewind
+#[derive(Clone, Copy)]
+enum HistoryEntry {
+    Update,
+    Step,
+    /// Maze edits incremental solvers repaired their search from
+    SetCell(GridPosition, u8),
+    SetStart(GridPosition),
+}
+
+impl HistoryEntry {
+    fn apply(self, solver: &mut dyn MazeSolverDrawable) {
+        match self {
+            Self::Update => solver.update(),
+            Self::Step => {
+                solver.step();
+            }
+            Self::SetCell(position, value) => {
+                solver.set_cell(&position, value);
+            }
+            Self::SetStart(position) => {
+                solver.set_start(&position);
+            }
+        }
+    }
+}
+
+/// 1 of the solvers compared side by side
+struct ComparisonPanel {
+    solver: Box<dyn MazeSolverDrawable>,
+    renderer: Renderer,
+    /// Counters are drawn below the maze in this area
+    area: Rect,
+    /// Steps taken until the search finished
+    steps: usize,
+}
+
+impl ComparisonPanel {
+    fn step(&mut self) {
+        if self.solver.outcome() == SearchOutcome::InProgress {
+            self.solver.step();
+            self.steps += 1;
+        }
+    }
+
+    fn draw(
+        &mut self,
+        ctx: &GgezContext,
+        canvas: &mut Canvas,
+        maze: &Maze,
+        overlays: &Overlays,
+    ) -> GameResult {
+        let solver = self.solver.as_ref();
+        self.renderer.clip(canvas)?;
+        self.renderer.draw_maze(ctx, canvas, maze);
+        self.renderer
+            .draw_maze_solver(ctx, canvas, solver, overlays)?;
+
+        // Finished paths are always shown so they can be compared
+        if solver.is_complete() {
+            if solver.has_waypoint_path() {
+                self.renderer
+                    .draw_polyline(ctx, canvas, solver.path(), MazeColor::PATH_TRACE.0)?;
+            } else {
+                let path = solver.path().get(1..).unwrap_or_default();
+                self.renderer
+                    .draw_cells(ctx, canvas, path, MazeColor::PATH_TRACE.0);
+            }
+        }
+
+        canvas.set_default_scissor_rect();
+
+        let path_cost = if solver.is_complete() {
+            format!("{:.2}", solver.path_cost())
+        } else {
+            String::from("-")
+        };
+        let mut text = Text::new(format!(
+            "{} - {:?}\nsteps {}   expanded {}   frontier {}   path cost {}",
+            solver.name(),
+            solver.outcome(),
+            self.steps,
+            expanded_text(solver),
+            solver.frontier_len(),
+            path_cost
+        ));
+        text.set_scale(20.0);
+        let dest = Vec2::new(
+            self.area.x,
+            self.area.y + self.area.h - COMPARISON_STATS_HEIGHT,
+        );
+        canvas.draw(&text, DrawParam::new().dest(dest).color(Color::BLACK));
+
+        Ok(())
+    }
+}
+
+/// Panel areas tiling the window, side by side for 2 solvers and 2 by 2 for 3 or 4
+fn comparison_layout(screen: Rect, count: usize) -> Vec<Rect> {
+    let columns = count.min(2);
+    let rows = count.div_ceil(columns);
+    let width = screen.w / columns as f32;
+    let height = screen.h / rows as f32;
+
+    (0..count)
+        .map(|index| {
+            Rect::new(
+                screen.x + (index % columns) as f32 * width + COMPARISON_MARGIN,
+                screen.y + (index / columns) as f32 * height + COMPARISON_MARGIN,
+                (width - 2.0 * COMPARISON_MARGIN).max(0.0),
+                (height - 2.0 * COMPARISON_MARGIN).max(0.0),
+            )
+        })
+        .collect()
+}
+
+/// Size of a HUD text including its background padding
+fn hud_box_size(ctx: &GgezContext, text: &Text) -> GameResult<Vec2> {
+    Ok(Vec2::from(text.measure(ctx)?) + Vec2::splat(2.0 * HUD_PADDING))
+}
+
+/// HUD text on a translucent background, `size` as measured by `hud_box_size`
+fn draw_hud_box(canvas: &mut Canvas, text: &Text, dest: Vec2, size: Vec2) {
+    canvas.draw(
+        &Quad,
+        DrawParam::new()
+            .dest_rect(Rect::new(dest.x, dest.y, size.x, size.y))
+            .color(HUD_BACKGROUND),
+    );
+    canvas.draw(
+        text,
+        DrawParam::new()
+            .dest(dest + Vec2::splat(HUD_PADDING))
+            .color(Color::BLACK),
+    );
+}
+
+/// Expanded node count, followed by the scanned cells for solvers that skip over cells
+fn expanded_text(solver: &dyn MazeSolverDrawable) -> String {
+    match solver.scanned_cells() {
+        Some(scanned_cells) => format!("{} ({} scanned)", solver.expanded_count(), scanned_cells),
+        None => solver.expanded_count().to_string(),
+    }
+}
+
+fn hud_text(contents: impl Into<String>) -> Text {
+    let mut text = Text::new(contents.into());
+    text.set_scale(HUD_TEXT_SCALE);
+    text
+}
+
+/// Part of a comparison panel the maze is drawn in, above the counters
+fn comparison_maze_area(area: Rect) -> Rect {
+    Rect::new(
+        area.x,
+        area.y,
+        area.w,
+        (area.h - COMPARISON_STATS_HEIGHT).max(0.0),
+    )
+}
+
+struct GameState {
+    maze: Maze,
+    position_start: GridPosition,
+    position_end: GridPosition,
+    /// Goals besides `position_end`, searched for according to `goal_mode`
+    extra_goals: Vec<GridPosition>,
+    goal_mode: GoalMode,
+
+    current_algorithm: MazeSolveAlgorithm,
+    solver: Box<dyn MazeSolverDrawable>,
+    options: SolverOptions,
+
+    overlays: Overlays,
+
+    /// Solvers shown side by side when comparing
+    comparison_algorithms: Vec<MazeSolveAlgorithm>,
+    /// Empty unless comparing, the panels then replace the single solver view
+    panels: Vec<ComparisonPanel>,
+    /// Lock-step rounds taken by the panels, replayed to rewind
+    comparison_steps: usize,
+
+    /// Paused solvers only advance by single steps
+    paused: bool,
+    /// Index into SPEEDS
+    speed: usize,
+    /// Fraction of an update carried over to the next frame at speeds below 1
+    update_credit: f32,
+    /// Maze and start the solver was created with, the history is replayed on them to rewind
+    history_maze: Maze,
+    history_start: GridPosition,
+    history: Vec<HistoryEntry>,
+
+    renderer: Renderer,
+    /// Window area, follows resizes
+    screen: Rect,
+
+    path_trace: bool,
+    path_position_index: usize,
+    path_traced: Vec<GridPosition>,
+
+    mouse_edit: MouseEdit,
+
+    hud: Hud,
+
+    /// Maze being generated, solvers are paused until it is handed over
+    generator: Option<MazeGenerator>,
+    generate_algorithm: MazeGenerateAlgorithm,
+    generator_options: GeneratorOptions,
+    generation_steps_per_update: usize,
+}
+
+impl GameState {
+    fn new(
+        maze: Maze,
+        position_start: GridPosition,
+        position_end: GridPosition,
+        options: SolverOptions,
+    ) -> Self {
+        let current_algorithm = MazeSolveAlgorithm::AStar;
+        let solver =
+            current_algorithm.new_solver(maze.clone(), position_start, position_end, &options);
+
+        let renderer = Renderer::new_with_maze(&maze);
+
+        // Generate mazes with the same number of cells as the loaded one by default
+        let generator_options = GeneratorOptions {
+            rows: (maze.grid.len() / 2).max(1),
+            columns: (maze.grid[0].len() / 2).max(1),
+            ..Default::default()
+        };
+
+        Self {
+            maze: maze.clone(),
+            position_start,
+            position_end,
+            extra_goals: Vec::new(),
+            goal_mode: GoalMode::default(),
+            current_algorithm,
+            solver,
+            options,
+
+            overlays: Overlays::default(),
+
+            comparison_algorithms: vec![
+                MazeSolveAlgorithm::Bfs,
+                MazeSolveAlgorithm::Dfs,
+                MazeSolveAlgorithm::AStar,
+            ],
+            panels: Vec::new(),
+            comparison_steps: 0,
+
+            paused: false,
+            speed: DEFAULT_SPEED,
+            update_credit: 0.0,
+            history_maze: maze,
+            history_start: position_start,
+            history: Vec::new(),
+
+            renderer,
+            screen: Rect::new(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT),
+
+            path_trace: false,
+            path_position_index: 0,
+            path_traced: Vec::new(),
+
+            mouse_edit: MouseEdit::None,
+
+            hud: Hud::Status,
+
+            generator: None,
+            generate_algorithm: MazeGenerateAlgorithm::RecursiveBacktracker,
+            generator_options,
+            generation_steps_per_update: 1,
+        }
+    }
+
+    /// Start animating a new maze with the algorithm and a fresh seed
+    fn start_generation(&mut self, algorithm: MazeGenerateAlgorithm) {
+        self.generator_options.seed = rand::random();
+        self.begin_generation(algorithm);
+    }
+
+    fn begin_generation(&mut self, algorithm: MazeGenerateAlgorithm) {
+        log::info!(
+            "Generating {}x{} cell maze with {} algorithm, seed {}",
+            self.generator_options.rows,
+            self.generator_options.columns,
+            algorithm.name(),
+            self.generator_options.seed
+        );
+
+        let generator = MazeGenerator::new(algorithm, &self.generator_options);
+        self.generate_algorithm = algorithm;
+        self.generation_steps_per_update =
+            (self.generator_options.rows * self.generator_options.columns / GENERATION_UPDATES)
+                .max(1);
+        self.renderer = Renderer::new_with_maze_in(generator.maze(), self.screen);
+        self.generator = Some(generator);
+        self.panels.clear();
+        self.mouse_edit = MouseEdit::None;
+    }
+
+    /// Hand the generated maze over to the solvers
+    fn finish_generation(&mut self) {
+        let Some(generator) = self.generator.take() else {
+            return;
+        };
+
+        let connectivity = self.maze.connectivity;
+        self.maze = generator.generate();
+        self.maze.connectivity = connectivity;
+
+        // Generated mazes always mark both endpoints
+        self.position_start = self.maze.position_start.unwrap();
+        self.position_end = self.maze.position_end.unwrap();
+        self.extra_goals.clear();
+
+        self.renderer = Renderer::new_with_maze_in(&self.maze, self.screen);
+        self.reset_solver(self.current_algorithm);
+    }
+
+    /// Reset to re-draw start -> finish path
+    fn reset_path_rendering(&mut self) {
+        self.path_trace = false;
+        // Do not draw path over the starting node
+        self.path_position_index = 1;
+        self.path_traced.clear();
+    }
+
+    /// Start a new search with the algorithm, also used after the maze, start or end position changed.
+    /// Restarts the comparison too while comparing.
+    fn reset_solver(&mut self, algorithm: MazeSolveAlgorithm) {
+        self.current_algorithm = algorithm;
+        self.solver = self.new_solver(algorithm, self.maze.clone(), self.position_start);
+        self.history_maze = self.maze.clone();
+        self.history_start = self.position_start;
+        self.history.clear();
+        self.update_credit = 0.0;
+        self.reset_path_rendering();
+
+        if !self.panels.is_empty() {
+            self.start_comparison();
+        }
+    }
+
+    /// Switch to a single solver, leaving the comparison
+    fn select_algorithm(&mut self, algorithm: MazeSolveAlgorithm) {
+        self.panels.clear();
+        self.reset_solver(algorithm);
+    }
+
+    /// Restart every compared solver in its own panel, keeping the zoom of a running comparison
+    fn start_comparison(&mut self) {
+        let layout = comparison_layout(self.screen, self.comparison_algorithms.len());
+        let mut renderers = self
+            .panels
+            .drain(..)
+            .map(|panel| panel.renderer)
+            .collect::<Vec<_>>();
+        if renderers.len() != layout.len() {
+            renderers = layout
+                .iter()
+                .map(|&area| Renderer::new_with_maze_in(&self.maze, comparison_maze_area(area)))
+                .collect();
+        }
+
+        self.panels = self
+            .comparison_algorithms
+            .iter()
+            .zip(layout)
+            .zip(renderers)
+            .map(|((&algorithm, area), renderer)| ComparisonPanel {
+                solver: self.new_solver(algorithm, self.maze.clone(), self.position_start),
+                renderer,
+                area,
+                steps: 0,
+            })
+            .collect();
+        self.comparison_steps = 0;
+        self.update_credit = 0.0;
+    }
+
+    /// Step every compared solver still searching once
+    fn step_comparison(&mut self) {
+        if self
+            .panels
+            .iter()
+            .any(|panel| panel.solver.outcome() == SearchOutcome::InProgress)
+        {
+            self.panels.iter_mut().for_each(ComparisonPanel::step);
+            self.comparison_steps += 1;
+        }
+    }
+
+    /// Restart the comparison and replay all but the last `count` rounds of steps
+    fn rewind_comparison(&mut self, count: usize) {
+        let steps = self.comparison_steps.saturating_sub(count);
+        self.start_comparison();
+        for _ in 0..steps {
+            self.step_comparison();
+        }
+        log::info!("Rewound comparison to step {}", steps);
+    }
+
+    fn new_solver(
+        &self,
+        algorithm: MazeSolveAlgorithm,
+        maze: Maze,
+        position_start: GridPosition,
+    ) -> Box<dyn MazeSolverDrawable> {
+        if self.extra_goals.is_empty() {
+            return algorithm.new_solver(maze, position_start, self.position_end, &self.options);
+        }
+
+        let mut goals = vec![self.position_end];
+        goals.extend_from_slice(&self.extra_goals);
+        self.goal_mode
+            .new_solver(
+                algorithm,
+                maze.clone(),
+                position_start,
+                goals.clone(),
+                &self.options,
+            )
+            .unwrap_or_else(|| {
+                log::warn!("{:?} only searches for a single goal, using A*", algorithm);
+                MazeSolveAlgorithm::AStar
+                    .new_nearest_goal_solver(maze, position_start, goals, &self.options)
+                    .unwrap()
+            })
+    }
+
+    /// Advance a search in progress by an update or a single step, recording it for rewinding
+    fn advance(&mut self, entry: HistoryEntry) {
+        if self.solver.outcome() == SearchOutcome::InProgress {
+            entry.apply(self.solver.as_mut());
+            self.history.push(entry);
+        }
+    }
+
+    /// Undo the last `count` updates and steps by replaying the rest of the history on a new solver.
+    /// Stops at maze edits the solver repaired its search from.
+    fn rewind(&mut self, count: usize) {
+        let mut rewound = 0;
+        while rewound < count
+            && matches!(
+                self.history.last(),
+                Some(HistoryEntry::Update | HistoryEntry::Step)
+            )
+        {
+            self.history.pop();
+            rewound += 1;
+        }
+        if rewound == 0 {
+            return;
+        }
+
+        let mut solver = self.new_solver(
+            self.current_algorithm,
+            self.history_maze.clone(),
+            self.history_start,
+        );
+        for &entry in &self.history {
+            entry.apply(solver.as_mut());
+        }
+        self.solver = solver;
+        self.reset_path_rendering();
+        log::info!("Rewound to {} expanded nodes", self.solver.expanded_count());
+    }
+
+    fn set_speed(&mut self, speed: usize) {
+        self.speed = speed.min(SPEEDS.len() - 1);
+        log::info!("Speed: {} solver updates per frame", SPEEDS[self.speed]);
+    }
+
+    /// Add an extra goal, or remove it if the cell already is one
+    fn toggle_goal(&mut self, cell: GridPosition) {
+        if let Some(index) = self.extra_goals.iter().position(|&goal| goal == cell) {
+            self.extra_goals.remove(index);
+        } else if cell != self.position_start
+            && cell != self.position_end
+            && self.maze.is_cell_valid(&cell)
+        {
+            self.extra_goals.push(cell);
+        } else {
+            return;
+        }
+        self.reset_solver(self.current_algorithm);
+    }
+
+    /// Maze cell under a screen position, in any panel while comparing
+    fn cell_at(&self, x: f32, y: f32) -> Option<GridPosition> {
+        if self.panels.is_empty() {
+            self.renderer.cell_at(x, y)
+        } else {
+            self.panels
+                .iter()
+                .find_map(|panel| panel.renderer.cell_at(x, y))
+        }
+    }
+
+    /// Lay the maze views out in a resized window
+    fn resize(&mut self, screen: Rect) {
+        self.screen = screen;
+        self.renderer.resize(screen);
+
+        let layout = comparison_layout(screen, self.panels.len());
+        for (panel, area) in self.panels.iter_mut().zip(layout) {
+            panel.area = area;
+            panel.renderer.resize(comparison_maze_area(area));
+        }
+    }
+
+    /// Zoom about a screen position, comparison panels zoom together about the same point in each
+    fn zoom_at(&mut self, point: Vec2, factor: f32) {
+        if self.panels.is_empty() {
+            self.renderer.zoom_at(point, factor);
+            return;
+        }
+
+        let Some(hovered) = self.panels.iter().find(|panel| panel.area.contains(point)) else {
+            return;
+        };
+        let offset = point - Vec2::from(hovered.renderer.area().point());
+        for panel in &mut self.panels {
+            let area = panel.renderer.area();
+            panel
+                .renderer
+                .zoom_at(Vec2::from(area.point()) + offset, factor);
+        }
+    }
+
+    fn pan(&mut self, offset: Vec2) {
+        if self.panels.is_empty() {
+            self.renderer.pan(offset);
+        } else {
+            for panel in &mut self.panels {
+                panel.renderer.pan(offset);
+            }
+        }
+    }
+
+    fn reset_view(&mut self) {
+        self.renderer.reset_view();
+        for panel in &mut self.panels {
+            panel.renderer.reset_view();
+        }
+    }
+
+    /// Algorithm, search statistics and viewer settings, the compared solvers show their own statistics
+    fn hud_status(&self) -> String {
+        if self.generator.is_some() {
+            return format!(
+                "Generating {}x{} maze with {}\nF1 - key help",
+                self.generator_options.rows,
+                self.generator_options.columns,
+                self.generate_algorithm.name()
+            );
+        }
+
+        let mut lines = Vec::new();
+        if self.panels.is_empty() {
+            let solver = self.solver.as_ref();
+            lines.push(format!("{} - {:?}", solver.name(), solver.outcome()));
+            lines.push(format!(
+                "expanded {}   frontier {}",
+                expanded_text(solver),
+                solver.frontier_len()
+            ));
+            if solver.outcome() == SearchOutcome::Found {
+                lines.push(format!(
+                    "path cost {:.2}   path length {}",
+                    solver.path_cost(),
+                    solver.path().len()
+                ));
+            }
+        } else {
+            lines.push(format!("Comparing {} solvers", self.panels.len()));
+        }
+
+        let heuristic = self
+            .options
+            .heuristic
+            .unwrap_or_else(|| Heuristic::default_for(self.maze.connectivity));
+        lines.push(format!(
+            "{:?} connectivity   {:?} heuristic",
+            self.maze.connectivity, heuristic
+        ));
+        if !self.extra_goals.is_empty() {
+            lines.push(format!(
+                "{} goals, {:?}",
+                self.extra_goals.len() + 1,
+                self.goal_mode
+            ));
+        }
+        lines.push(format!(
+            "speed {}x{}",
+            SPEEDS[self.speed],
+            if self.paused { "   paused" } else { "" }
+        ));
+
+        let overlays = [
+            (self.overlays.frontier, "frontier"),
+            (self.overlays.values, "values"),
+            (self.overlays.heatmap, "heatmap"),
+            (self.overlays.parents, "parents"),
+        ]
+        .iter()
+        .filter_map(|&(shown, name)| shown.then_some(name))
+        .collect::<Vec<_>>();
+        if !overlays.is_empty() {
+            lines.push(format!("overlays: {}", overlays.join(", ")));
+        }
+        if self.hud == Hud::Status {
+            lines.push(String::from("F1 - key help"));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Status in the top left corner and the key bindings in the top right one
+    fn draw_hud(&self, ctx: &GgezContext, canvas: &mut Canvas) -> GameResult {
+        if self.hud == Hud::Hidden {
+            return Ok(());
+        }
+
+        let status = hud_text(self.hud_status());
+        let size = hud_box_size(ctx, &status)?;
+        draw_hud_box(canvas, &status, Vec2::splat(HUD_MARGIN), size);
+
+        if self.hud == Hud::Help {
+            let help = hud_text(KEY_HELP);
+            let size = hud_box_size(ctx, &help)?;
+            let dest = Vec2::new(
+                (self.screen.w - size.x - HUD_MARGIN).max(HUD_MARGIN),
+                HUD_MARGIN,
+            );
+            draw_hud_box(canvas, &help, dest, size);
+        }
+
+        Ok(())
+    }
+
+    /// Apply the current mouse edit to the cell under the cursor
+    fn apply_mouse_edit(&mut self, x: f32, y: f32) {
+        if self.generator.is_some() {
+            return;
+        }
+        let Some(cell) = self.cell_at(x, y) else {
+            return;
+        };
+
+        // Start and end positions can only be moved onto free cells, and walls never cover them
+        let is_endpoint = cell == self.position_start
+            || cell == self.position_end
+            || self.extra_goals.contains(&cell);
+        match self.mouse_edit {
+            MouseEdit::MoveStart if !is_endpoint && self.maze.is_cell_valid(&cell) => {
+                self.position_start = cell;
+                let repaired = self.solver.set_start(&cell);
+                self.restart_unless_repaired(repaired, HistoryEntry::SetStart(cell));
+            }
+            MouseEdit::MoveEnd if !is_endpoint && self.maze.is_cell_valid(&cell) => {
+                self.position_end = cell;
+                self.reset_solver(self.current_algorithm);
+            }
+            MouseEdit::PaintWall if !is_endpoint => self.set_cell(&cell, CELL_WALL),
+            MouseEdit::EraseWall => self.set_cell(&cell, CELL_EMPTY),
+            _ => {}
+        }
+    }
+
+    /// Change a maze cell, incremental solvers repair their search and the others start over
+    fn set_cell(&mut self, position: &GridPosition, value: u8) {
+        let cell = &mut self.maze.grid[position.x as usize][position.y as usize];
+        if *cell == value {
+            return;
+        }
+        *cell = value;
+
+        let repaired = self.solver.set_cell(position, value);
+        self.restart_unless_repaired(repaired, HistoryEntry::SetCell(*position, value));
+    }
+
+    /// Compared solvers always restart
+    fn restart_unless_repaired(&mut self, repaired: bool, edit: HistoryEntry) {
+        if repaired && self.panels.is_empty() {
+            self.history.push(edit);
+            self.reset_path_rendering();
+        } else {
+            self.reset_solver(self.current_algorithm);
+        }
+    }
+}
+
+impl EventHandler for GameState {
+    fn update(&mut self, ctx: &mut GgezContext) -> GameResult {
+        while ctx.time.check_update_time(TARGET_FPS) {
+            if let Some(generator) = self.generator.as_mut() {
+                for _ in 0..self.generation_steps_per_update {
+                    generator.step();
+                }
+                if generator.is_complete() {
+                    self.finish_generation();
+                }
+                continue;
+            }
+
+            if self.paused {
+                continue;
+            }
+
+            if !self.panels.is_empty() {
+                self.update_credit += SPEEDS[self.speed] * COMPARISON_STEPS_PER_UPDATE;
+                while self.update_credit >= 1.0 {
+                    self.update_credit -= 1.0;
+                    self.step_comparison();
+                }
+                continue;
+            }
+
+            self.update_credit += SPEEDS[self.speed];
+            while self.update_credit >= 1.0 {
+                self.update_credit -= 1.0;
+                self.advance(HistoryEntry::Update);
+            }
+
+            // Update path tracing
+            let solver = self.solver.as_mut();
+            if self.path_trace
+                && solver.is_complete()
+                && self.path_position_index < solver.path().len()
+            {
+                self.path_traced
+                    .push(solver.path()[self.path_position_index]);
+                self.path_position_index += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut GgezContext) -> GameResult {
+        let mut canvas = Canvas::from_frame(ctx, Color::WHITE);
+
+        if let Some(generator) = &self.generator {
+            self.renderer
+                .draw_maze_generator(ctx, &mut canvas, generator);
+            self.draw_hud(ctx, &mut canvas)?;
+            canvas.finish(ctx)?;
+            ggez::timer::yield_now();
+            return Ok(());
+        }
+
+        if !self.panels.is_empty() {
+            for panel in &mut self.panels {
+                panel.draw(ctx, &mut canvas, &self.maze, &self.overlays)?;
+            }
+            self.draw_hud(ctx, &mut canvas)?;
+            canvas.finish(ctx)?;
+            ggez::timer::yield_now();
+            return Ok(());
+        }
+
+        // Draw base maze
+        self.renderer.draw_maze(ctx, &mut canvas, &self.maze);
+
+        self.renderer
+            .draw_maze_solver(ctx, &mut canvas, self.solver.as_ref(), &self.overlays)?;
+
+        // Trace path, any-angle paths are traced segment by segment from the start
+        if self.solver.has_waypoint_path() {
+            let mut waypoints = vec![*self.solver.start()];
+            waypoints.extend_from_slice(&self.path_traced);
+            self.renderer
+                .draw_polyline(ctx, &mut canvas, &waypoints, MazeColor::PATH_TRACE.0)?;
+        } else {
+            self.renderer
+                .draw_cells(ctx, &mut canvas, &self.path_traced, MazeColor::PATH_TRACE.0);
+        }
+
+        self.draw_hud(ctx, &mut canvas)?;
+
+        canvas.finish(ctx)?;
+
+        ggez::timer::yield_now();
+
+        Ok(())
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut GgezContext,
+        input: KeyInput,
+        _repeat: bool,
+    ) -> GameResult {
+        // Only generation can be restarted while a maze is being generated
+        if self.generator.is_some()
+            && !matches!(input.keycode, Some(KeyCode::G | KeyCode::N | KeyCode::F1))
+        {
+            return Ok(());
+        }
+
+        match input.keycode {
+            Some(KeyCode::G) => self.start_generation(self.generate_algorithm),
+            Some(KeyCode::N) => {
+                let index = MazeGenerateAlgorithm::ALL
+                    .iter()
+                    .position(|&algorithm| algorithm == self.generate_algorithm)
+                    .unwrap();
+                let algorithm =
+                    MazeGenerateAlgorithm::ALL[(index + 1) % MazeGenerateAlgorithm::ALL.len()];
+                self.start_generation(algorithm);
+            }
+            Some(KeyCode::B) if input.mods.contains(KeyMods::SHIFT) => {
+                self.select_algorithm(MazeSolveAlgorithm::BidirectionalBfs)
+            }
+            Some(KeyCode::A) if input.mods.contains(KeyMods::SHIFT) => {
+                self.select_algorithm(MazeSolveAlgorithm::BidirectionalAStar)
+            }
+            Some(KeyCode::D) if input.mods.contains(KeyMods::SHIFT) => {
+                self.select_algorithm(MazeSolveAlgorithm::Iddfs)
+            }
+            Some(KeyCode::I) => self.select_algorithm(MazeSolveAlgorithm::IdaStar),
+            Some(KeyCode::E) => self.select_algorithm(MazeSolveAlgorithm::GreedyBestFirst),
+            Some(KeyCode::W) => self.select_algorithm(MazeSolveAlgorithm::WeightedAStar),
+            Some(KeyCode::K) => self.select_algorithm(MazeSolveAlgorithm::Beam),
+            Some(KeyCode::P) => self.select_algorithm(MazeSolveAlgorithm::Jps),
+            Some(KeyCode::Y) if input.mods.contains(KeyMods::SHIFT) => {
+                self.select_algorithm(MazeSolveAlgorithm::LazyThetaStar)
+            }
+            Some(KeyCode::Y) => self.select_algorithm(MazeSolveAlgorithm::ThetaStar),
+            Some(KeyCode::L) if input.mods.contains(KeyMods::SHIFT) => {
+                self.select_algorithm(MazeSolveAlgorithm::DStarLite)
+            }
+            Some(KeyCode::L) => self.select_algorithm(MazeSolveAlgorithm::LpaStar),
+            Some(KeyCode::B) => self.select_algorithm(MazeSolveAlgorithm::Bfs),
+            Some(KeyCode::D) => self.select_algorithm(MazeSolveAlgorithm::Dfs),
+            Some(KeyCode::J) => self.select_algorithm(MazeSolveAlgorithm::Dijkstra),
+            Some(KeyCode::A) => self.select_algorithm(MazeSolveAlgorithm::AStar),
+            Some(KeyCode::C) => {
+                self.maze.connectivity = match self.maze.connectivity {
+                    Connectivity::Four => Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+                    Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls) => {
+                        Connectivity::Eight(DiagonalMovement::IfAtMostOneWall)
+                    }
+                    Connectivity::Eight(DiagonalMovement::IfAtMostOneWall) => {
+                        Connectivity::Eight(DiagonalMovement::Always)
+                    }
+                    Connectivity::Eight(DiagonalMovement::Always) => Connectivity::Four,
+                };
+                log::info!("Connectivity: {:?}", self.maze.connectivity);
+                self.reset_solver(self.current_algorithm);
+            }
+            Some(KeyCode::M) => {
+                self.goal_mode = match self.goal_mode {
+                    GoalMode::Nearest => GoalMode::All,
+                    GoalMode::All => GoalMode::Nearest,
+                };
+                log::info!("Goal mode: {:?}", self.goal_mode);
+                self.reset_solver(self.current_algorithm);
+            }
+            Some(KeyCode::H) => {
+                // Cycle through the heuristics, starting from the connectivity default
+                let heuristic = self
+                    .options
+                    .heuristic
+                    .unwrap_or_else(|| Heuristic::default_for(self.maze.connectivity));
+                let index = Heuristic::ALL.iter().position(|&h| h == heuristic).unwrap();
+                let heuristic = Heuristic::ALL[(index + 1) % Heuristic::ALL.len()];
+                log::info!("Heuristic: {:?}", heuristic);
+                self.options.heuristic = Some(heuristic);
+                self.reset_solver(self.current_algorithm);
+            }
+            Some(KeyCode::T) => {
+                self.reset_path_rendering();
+                self.path_trace = true;
+            }
+            Some(KeyCode::Space) => {
+                self.paused = !self.paused;
+                log::info!("{}", if self.paused { "Paused" } else { "Resumed" });
+            }
+            Some(KeyCode::Right) => {
+                self.paused = true;
+                if self.panels.is_empty() {
+                    self.advance(HistoryEntry::Step);
+                } else {
+                    self.step_comparison();
+                }
+            }
+            Some(KeyCode::Left) => {
+                self.paused = true;
+                let count = if input.mods.contains(KeyMods::SHIFT) {
+                    10
+                } else {
+                    1
+                };
+                if self.panels.is_empty() {
+                    self.rewind(count);
+                } else {
+                    self.rewind_comparison(count);
+                }
+            }
+            Some(KeyCode::O) => {
+                if self.panels.is_empty() {
+                    log::info!("Comparing {:?}", self.comparison_algorithms);
+                    self.start_comparison();
+                } else {
+                    self.panels.clear();
+                }
+            }
+            Some(KeyCode::Key1) => {
+                self.overlays.frontier = !self.overlays.frontier;
+                log::info!("Frontier overlay: {}", self.overlays.frontier);
+            }
+            Some(KeyCode::Key2) => {
+                self.overlays.values = !self.overlays.values;
+                log::info!("g/h/f values overlay: {}", self.overlays.values);
+                if self.overlays.values && !self.renderer.can_draw_values() {
+                    log::warn!("Cells are too small to show their values");
+                }
+            }
+            Some(KeyCode::Key3) => {
+                self.overlays.heatmap = !self.overlays.heatmap;
+                log::info!("Expansion order heatmap: {}", self.overlays.heatmap);
+            }
+            Some(KeyCode::Key4) => {
+                self.overlays.parents = !self.overlays.parents;
+                log::info!("Parent arrows overlay: {}", self.overlays.parents);
+            }
+            Some(KeyCode::Up) => self.set_speed(self.speed + 1),
+            Some(KeyCode::Down) => self.set_speed(self.speed.saturating_sub(1)),
+            Some(KeyCode::R) => self.reset_solver(self.current_algorithm),
+            Some(KeyCode::Key0) => self.reset_view(),
+            Some(KeyCode::F1) => {
+                self.hud = match self.hud {
+                    Hud::Status => Hud::Help,
+                    Hud::Help => Hud::Hidden,
+                    Hud::Hidden => Hud::Status,
+                };
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn mouse_button_down_event(
+        &mut self,
+        ctx: &mut GgezContext,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult {
+        // Middle button or Ctrl + left button drags the view
+        if button == MouseButton::Middle
+            || (button == MouseButton::Left && ctx.keyboard.is_mod_active(KeyMods::CTRL))
+        {
+            self.mouse_edit = MouseEdit::Pan;
+            return Ok(());
+        }
+
+        if self.generator.is_some() {
+            return Ok(());
+        }
+        let Some(cell) = self.cell_at(x, y) else {
+            return Ok(());
+        };
+
+        // Shift + left click adds or removes extra goals
+        if button == MouseButton::Left && ctx.keyboard.is_mod_active(KeyMods::SHIFT) {
+            self.toggle_goal(cell);
+            return Ok(());
+        }
+
+        // Left button drags the start/end positions or paints walls, right button erases walls
+        self.mouse_edit = match button {
+            MouseButton::Left if cell == self.position_start => MouseEdit::MoveStart,
+            MouseButton::Left if cell == self.position_end => MouseEdit::MoveEnd,
+            MouseButton::Left => MouseEdit::PaintWall,
+            MouseButton::Right => MouseEdit::EraseWall,
+            _ => MouseEdit::None,
+        };
+        self.apply_mouse_edit(x, y);
+
+        Ok(())
+    }
+
+    fn mouse_button_up_event(
+        &mut self,
+        _ctx: &mut GgezContext,
+        _button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) -> GameResult {
+        self.mouse_edit = MouseEdit::None;
+        Ok(())
+    }
+
+    fn mouse_motion_event(
+        &mut self,
+        _ctx: &mut GgezContext,
+        x: f32,
+        y: f32,
+        dx: f32,
+        dy: f32,
+    ) -> GameResult {
+        if self.mouse_edit == MouseEdit::Pan {
+            self.pan(Vec2::new(dx, dy));
+        } else {
+            self.apply_mouse_edit(x, y);
+        }
+        Ok(())
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut GgezContext, _x: f32, y: f32) -> GameResult {
+        self.zoom_at(ctx.mouse.position().into(), ZOOM_STEP.powf(y));
+        Ok(())
+    }
+
+    fn resize_event(&mut self, _ctx: &mut GgezContext, width: f32, height: f32) -> GameResult {
+        self.resize(Rect::new(0.0, 0.0, width, height));
+        Ok(())
+    }
+}
+
+/// Open the viewer window and run the event loop until it is closed.
+/// The first goal can be dragged, the others are searched for according to the goal mode.
+/// Starts comparing the algorithms side by side if 2 to 4 are given.
+pub fn run(
+    maze: Maze,
+    position_start: GridPosition,
+    goals: Vec<GridPosition>,
+    goal_mode: GoalMode,
+    options: SolverOptions,
+    compare: Vec<MazeSolveAlgorithm>,
+) -> GameResult {
+    let mut state = GameState::new(maze, position_start, goals[0], options);
+    state.extra_goals = goals[1..].to_vec();
+    state.goal_mode = goal_mode;
+    if !state.extra_goals.is_empty() {
+        state.reset_solver(state.current_algorithm);
+    }
+    if !compare.is_empty() {
+        state.comparison_algorithms = compare;
+        state.start_comparison();
+    }
+
+    run_state(state)
+}
+
+/// Open the viewer animating a newly generated maze, which is then solved like a loaded one
+pub fn run_generation(
+    algorithm: MazeGenerateAlgorithm,
+    generator_options: GeneratorOptions,
+    options: SolverOptions,
+) -> GameResult {
+    // Solved once generation completes, the empty maze only sizes the window grid
+    let maze = MazeGenerator::new(algorithm, &generator_options)
+        .maze()
+        .clone();
+    let position_start = maze.position_start.unwrap();
+    let position_end = maze.position_end.unwrap();
+
+    let mut state = GameState::new(maze, position_start, position_end, options);
+    state.generator_options = generator_options;
+    state.begin_generation(algorithm);
+
+    run_state(state)
+}
+
+/// Plans collision-free paths for several agents, then moves all of them along their paths at once
+struct MapfState {
+    maze: Maze,
+    agents: Vec<Agent>,
+    max_nodes: usize,
+    solver: MapfSolver,
+
+    renderer: Renderer,
+    /// True while a mouse button drags the view
+    panning: bool,
+    show_hud: bool,
+
+    /// Time step of the animation, fractional while agents move between cells
+    time: f32,
+}
+
+impl MapfState {
+    fn new(maze: Maze, agents: Vec<Agent>, algorithm: MapfAlgorithm, max_nodes: usize) -> Self {
+        let renderer = Renderer::new_with_maze(&maze);
+        let solver =
+            MapfSolver::new(algorithm, maze.clone(), agents.clone()).with_max_nodes(max_nodes);
+
+        Self {
+            maze,
+            agents,
+            max_nodes,
+            solver,
+            renderer,
+            panning: false,
+            show_hud: true,
+            time: 0.0,
+        }
+    }
+
+    fn reset_solver(&mut self, algorithm: MapfAlgorithm) {
+        log::info!(
+            "Planning {} agents with {}",
+            self.agents.len(),
+            algorithm.name()
+        );
+        self.solver = MapfSolver::new(algorithm, self.maze.clone(), self.agents.clone())
+            .with_max_nodes(self.max_nodes);
+        self.time = 0.0;
+    }
+
+    /// Planning statistics, animation time step and the keys of this viewer
+    fn draw_hud(&self, ctx: &GgezContext, canvas: &mut Canvas) -> GameResult {
+        if !self.show_hud {
+            return Ok(());
+        }
+
+        let solver = &self.solver;
+        let mut lines = vec![format!(
+            "{} - {:?}   {} agents",
+            solver.name(),
+            solver.outcome(),
+            self.agents.len()
+        )];
+        let mut nodes = format!("expanded {}", solver.expanded_nodes());
+        if solver.algorithm() == MapfAlgorithm::ConflictBasedSearch {
+            nodes += &format!("   constraint tree nodes {}", solver.high_level_nodes());
+        }
+        lines.push(nodes);
+        if solver.outcome() == SearchOutcome::Found {
+            lines.push(format!(
+                "sum of costs {}   makespan {}   time step {}",
+                solver.sum_of_costs(),
+                solver.makespan(),
+                self.time.floor()
+            ));
+        }
+        lines.push(String::from(
+            "A - Cooperative A*   C - CBS   R - Replay   F1 - Hide\n\
+             Wheel - Zoom   Drag - Pan   0 - Fit maze to window",
+        ));
+
+        let text = hud_text(lines.join("\n"));
+        let size = hud_box_size(ctx, &text)?;
+        draw_hud_box(canvas, &text, Vec2::splat(HUD_MARGIN), size);
+
+        Ok(())
+    }
+}
+
+impl EventHandler for MapfState {
+    fn update(&mut self, ctx: &mut GgezContext) -> GameResult {
+        while ctx.time.check_update_time(TARGET_FPS) {
+            if self.solver.outcome() == SearchOutcome::InProgress {
+                for _ in 0..MAPF_STEPS_PER_UPDATE {
+                    self.solver.step();
+                }
+            } else if self.solver.outcome() == SearchOutcome::Found {
+                self.time = (self.time + AGENT_MOVES_PER_SECOND / TARGET_FPS as f32)
+                    .min(self.solver.makespan() as f32);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut GgezContext) -> GameResult {
+        let mut canvas = Canvas::from_frame(ctx, Color::WHITE);
+
+        self.renderer.draw_maze(ctx, &mut canvas, &self.maze);
+
+        for (index, agent) in self.agents.iter().enumerate() {
+            self.renderer
+                .draw_cell(&mut canvas, &agent.goal, MazeColor::agent_goal(index));
+        }
+
+        // Agents wait at their starts until every path is known
+        let found = self.solver.outcome() == SearchOutcome::Found;
+        let step = self.time.floor() as usize;
+        for (index, agent) in self.agents.iter().enumerate() {
+            let (from, to) = if found {
+                (
+                    self.solver.position_at(index, step),
+                    self.solver.position_at(index, step + 1),
+                )
+            } else {
+                (agent.start, agent.start)
+            };
+            self.renderer.draw_agent(
+                &mut canvas,
+                &from,
+                &to,
+                self.time.fract(),
+                MazeColor::agent(index),
+            );
+        }
+
+        self.draw_hud(ctx, &mut canvas)?;
+
+        canvas.finish(ctx)?;
+
+        ggez::timer::yield_now();
+
+        Ok(())
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut GgezContext,
+        input: KeyInput,
+        _repeat: bool,
+    ) -> GameResult {
+        match input.keycode {
+            Some(KeyCode::A) => self.reset_solver(MapfAlgorithm::CooperativeAStar),
+            Some(KeyCode::C) => self.reset_solver(MapfAlgorithm::ConflictBasedSearch),
+            Some(KeyCode::R) => self.time = 0.0,
+            Some(KeyCode::Key0) => self.renderer.reset_view(),
+            Some(KeyCode::F1) => self.show_hud = !self.show_hud,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Nothing is edited, any button drags the view
+    fn mouse_button_down_event(
+        &mut self,
+        _ctx: &mut GgezContext,
+        _button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) -> GameResult {
+        self.panning = true;
+        Ok(())
+    }
+
+    fn mouse_button_up_event(
+        &mut self,
+        _ctx: &mut GgezContext,
+        _button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) -> GameResult {
+        self.panning = false;
+        Ok(())
+    }
+
+    fn mouse_motion_event(
+        &mut self,
+        _ctx: &mut GgezContext,
+        _x: f32,
+        _y: f32,
+        dx: f32,
+        dy: f32,
+    ) -> GameResult {
+        if self.panning {
+            self.renderer.pan(Vec2::new(dx, dy));
+        }
+        Ok(())
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut GgezContext, _x: f32, y: f32) -> GameResult {
+        self.renderer
+            .zoom_at(ctx.mouse.position().into(), ZOOM_STEP.powf(y));
+        Ok(())
+    }
+
+    fn resize_event(&mut self, _ctx: &mut GgezContext, width: f32, height: f32) -> GameResult {
+        self.renderer.resize(Rect::new(0.0, 0.0, width, height));
+        Ok(())
+    }
+}
+
+/// Open the viewer planning paths for the agents with the algorithm, then animating them together
+pub fn run_mapf(
+    maze: Maze,
+    agents: Vec<Agent>,
+    algorithm: MapfAlgorithm,
+    max_nodes: usize,
+) -> GameResult {
+    run_state(MapfState::new(maze, agents, algorithm, max_nodes))
+}
+
+fn run_state(state: impl EventHandler + 'static) -> GameResult {
+    let (ctx, events_loop) = ggez::ContextBuilder::new("MazeSolver", "fairywreath")
+        .window_setup(ggez::conf::WindowSetup::default().title("Maze Solver"))
+        .window_mode(
+            ggez::conf::WindowMode::default()
+                .dimensions(SCREEN_WIDTH, SCREEN_HEIGHT)
+                .resizable(true),
+        )
+        .build()?;
+
+    event::run(ctx, events_loop, state)
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+#[test]
+fn astar_matches_bfs_on_bundled_maze() {
+    let maze_string =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/maze.txt")).unwrap();
+    let maze = Maze::new_from_string(&maze_string).unwrap();
+    let start = maze.position_start.unwrap();
+    let end = maze.position_end.unwrap();
+
+    let mut bfs = MazeSolverBFS::new(maze.clone(), start, end);
+    bfs.run();
+    let mut astar = MazeSolverAStar::new(maze.clone(), start, end);
+    astar.run();
+
+    assert!(astar.is_complete());
+    assert_path_valid(&maze, astar.path(), start, end);
+    assert_eq!(astar.path().len(), bfs.path().len());
+    assert!(astar.expanded_nodes().len() <= bfs.expanded_nodes().len());
+}
+
+#[test]
+fn astar_matches_bfs_on_unit_cost_mazes() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..300 {
+        let rows = 2 + rng.below(30);
+        let columns = 2 + rng.below(30);
+        let maze = random_maze(&mut rng, rows, columns, 0);
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut bfs = MazeSolverBFS::new(maze.clone(), start, end);
+        bfs.run();
+        for heuristic in [Heuristic::Manhattan, Heuristic::Euclidean, Heuristic::Zero] {
+            let mut astar =
+                MazeSolverAStar::new(maze.clone(), start, end).with_heuristic(heuristic);
+            astar.run();
+
+            assert_eq!(astar.outcome(), bfs.outcome());
+            if astar.is_complete() {
+                assert_path_valid(&maze, astar.path(), start, end);
+                assert_eq!(astar.path().len(), bfs.path().len(), "{:?}", heuristic);
+            }
+        }
+    }
+}
+
+#[test]
+fn astar_matches_dijkstra_on_weighted_mazes() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let connectivities = [
+        Connectivity::Four,
+        Connectivity::Eight(DiagonalMovement::Always),
+        Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+        Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+    ];
+
+    for _ in 0..300 {
+        let rows = 2 + rng.below(30);
+        let columns = 2 + rng.below(30);
+        let mut maze = random_maze(&mut rng, rows, columns, 9);
+        maze.connectivity = connectivities[rng.below(connectivities.len())];
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut dijkstra = MazeSolverDijkstra::new(maze.clone(), start, end);
+        dijkstra.run();
+        let mut astar = MazeSolverAStar::new(maze.clone(), start, end);
+        astar.run();
+
+        assert_eq!(astar.outcome(), dijkstra.outcome());
+        if astar.is_complete() {
+            assert_path_valid(&maze, astar.path(), start, end);
+            assert!((astar.path_cost() - dijkstra.path_cost()).abs() < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn astar_start_is_goal() {
+    let maze = Maze::new_from_string("S.\n..").unwrap();
+    let start = maze.position_start.unwrap();
+
+    let mut astar = MazeSolverAStar::new(maze, start, start);
+    astar.run();
+
+    assert!(astar.is_complete());
+    assert_eq!(astar.path(), &vec![start]);
+    assert_eq!(astar.path_cost(), 0.0);
+}
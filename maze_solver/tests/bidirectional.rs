@@ -0,0 +1,92 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+const CONNECTIVITIES: [Connectivity; 4] = [
+    Connectivity::Four,
+    Connectivity::Eight(DiagonalMovement::Always),
+    Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+    Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+];
+
+#[test]
+fn bidirectional_bfs_matches_bfs() {
+    let mut rng = Rng(0x243f_6a88_85a3_08d3);
+    for _ in 0..300 {
+        let rows = 1 + rng.below(30);
+        let columns = 1 + rng.below(30);
+        let mut maze = random_maze(&mut rng, rows, columns, 0);
+        maze.connectivity = CONNECTIVITIES[rng.below(CONNECTIVITIES.len())];
+        if !maze.grid.iter().flatten().any(|&cell| cell == 0) {
+            continue;
+        }
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut bfs = MazeSolverBFS::new(maze.clone(), start, end);
+        bfs.run();
+        let mut bidirectional = MazeSolverBidirectionalBFS::new(maze.clone(), start, end);
+        bidirectional.run();
+
+        assert_eq!(bidirectional.outcome(), bfs.outcome());
+        if bidirectional.is_complete() {
+            assert_path_valid(&maze, bidirectional.path(), start, end);
+            assert_eq!(
+                bidirectional.path().len(),
+                bfs.path().len(),
+                "{:?}",
+                maze.connectivity
+            );
+        }
+    }
+}
+
+#[test]
+fn bidirectional_astar_matches_dijkstra_on_weighted_mazes() {
+    let mut rng = Rng(0x1319_8a2e_0370_7344);
+    for _ in 0..300 {
+        let rows = 1 + rng.below(30);
+        let columns = 1 + rng.below(30);
+        let mut maze = random_maze(&mut rng, rows, columns, 9);
+        maze.connectivity = CONNECTIVITIES[rng.below(CONNECTIVITIES.len())];
+        if !maze.grid.iter().flatten().any(|&cell| cell != 1) {
+            continue;
+        }
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut dijkstra = MazeSolverDijkstra::new(maze.clone(), start, end);
+        dijkstra.run();
+        let mut bidirectional = MazeSolverBidirectionalAStar::new(maze.clone(), start, end);
+        bidirectional.run();
+
+        assert_eq!(bidirectional.outcome(), dijkstra.outcome());
+        if bidirectional.is_complete() {
+            assert_path_valid(&maze, bidirectional.path(), start, end);
+            assert!(
+                (bidirectional.path_cost() - dijkstra.path_cost()).abs() < 1e-3,
+                "{:?}: {} != {}",
+                maze.connectivity,
+                bidirectional.path_cost(),
+                dijkstra.path_cost()
+            );
+        }
+    }
+}
+
+#[test]
+fn bidirectional_searches_start_is_goal() {
+    let maze = Maze::new_from_string("S.\n..").unwrap();
+    let start = maze.position_start.unwrap();
+
+    let mut bfs = MazeSolverBidirectionalBFS::new(maze.clone(), start, start);
+    bfs.run();
+    assert!(bfs.is_complete());
+    assert_eq!(bfs.path(), &vec![start]);
+
+    let mut astar = MazeSolverBidirectionalAStar::new(maze, start, start);
+    astar.run();
+    assert!(astar.is_complete());
+    assert_eq!(astar.path(), &vec![start]);
+}
@@ -0,0 +1,73 @@
+#![allow(dead_code)]
+
+use maze_solver::*;
+
+/// Small deterministic xorshift generator so the test mazes do not need a rand dependency
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Random maze with the given wall density and terrain costs up to `max_cost`, 0 for unit cost
+pub fn random_maze(rng: &mut Rng, rows: usize, columns: usize, max_cost: u8) -> Maze {
+    let grid = (0..rows)
+        .map(|_| {
+            (0..columns)
+                .map(|_| match rng.below(10) {
+                    0..=2 => 1,
+                    _ if max_cost >= 2 && rng.below(3) == 0 => {
+                        2 + rng.below((max_cost - 1) as _) as u8
+                    }
+                    _ => 0,
+                })
+                .collect()
+        })
+        .collect();
+
+    Maze {
+        grid,
+        position_start: None,
+        position_end: None,
+        connectivity: Connectivity::Four,
+    }
+}
+
+pub fn random_open_cell(rng: &mut Rng, maze: &Maze) -> GridPosition {
+    loop {
+        let position = GridPosition::new(
+            rng.below(maze.grid.len()) as _,
+            rng.below(maze.grid[0].len()) as _,
+        );
+        if maze.is_cell_valid(&position) {
+            return position;
+        }
+    }
+}
+
+pub fn assert_path_valid(
+    maze: &Maze,
+    path: &[GridPosition],
+    start: GridPosition,
+    end: GridPosition,
+) {
+    assert_eq!(path.first(), Some(&start));
+    assert_eq!(path.last(), Some(&end));
+    for step in path.windows(2) {
+        assert!(
+            maze.neighbours(&step[0]).any(|cell| cell == step[1]),
+            "invalid move {:?} -> {:?}",
+            step[0],
+            step[1]
+        );
+    }
+}
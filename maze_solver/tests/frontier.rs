@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+const CONNECTIVITIES: [Connectivity; 4] = [
+    Connectivity::Four,
+    Connectivity::Eight(DiagonalMovement::Always),
+    Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+    Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+];
+
+fn solvers(
+    maze: &Maze,
+    start: GridPosition,
+    end: GridPosition,
+) -> Vec<Box<dyn MazeSolverDrawable>> {
+    let maze = || maze.clone();
+    vec![
+        Box::new(MazeSolverBFS::new(maze(), start, end)),
+        Box::new(MazeSolverDFS::new(maze(), start, end)),
+        Box::new(MazeSolverDijkstra::new(maze(), start, end)),
+        Box::new(MazeSolverAStar::new(maze(), start, end)),
+        Box::new(MazeSolverBidirectionalBFS::new(maze(), start, end)),
+        Box::new(MazeSolverBidirectionalAStar::new(maze(), start, end)),
+        Box::new(MazeSolverIDDFS::new(maze(), start, end)),
+        Box::new(MazeSolverIDAStar::new(maze(), start, end)),
+        Box::new(MazeSolverGreedyBestFirst::new(maze(), start, end)),
+        Box::new(MazeSolverBeam::new(maze(), start, end, 4)),
+        Box::new(MazeSolverJPS::new(maze(), start, end)),
+        Box::new(MazeSolverThetaStar::new(maze(), start, end)),
+        Box::new(MazeSolverThetaStar::new(maze(), start, end).lazy()),
+        Box::new(MazeSolverLPAStar::new(maze(), start, end)),
+        Box::new(MazeSolverDStarLite::new(maze(), start, end)),
+        Box::new(MazeSolverAllGoals::new(maze(), start, vec![end, start])),
+    ]
+}
+
+#[test]
+fn frontier_len_matches_frontier() {
+    let mut rng = Rng(0x1319_8a2e_0370_7344);
+    for _ in 0..50 {
+        let mut maze = random_maze(&mut rng, 12, 12, 0);
+        maze.connectivity = CONNECTIVITIES[rng.below(CONNECTIVITIES.len())];
+        if !maze.grid.iter().flatten().any(|&cell| cell == 0) {
+            continue;
+        }
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        for mut solver in solvers(&maze, start, end) {
+            for _ in 0..200 {
+                assert_eq!(
+                    solver.frontier_len(),
+                    solver.frontier().len(),
+                    "{} frontier size",
+                    solver.name()
+                );
+                if !solver.is_complete() {
+                    solver.update();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use maze_solver::{parser::format_maze, *};
+
+fn open_cells(maze: &Maze) -> Vec<GridPosition> {
+    (0..maze.grid.len())
+        .flat_map(|x| (0..maze.grid[0].len()).map(move |y| GridPosition::new(x as _, y as _)))
+        .filter(|position| maze.is_cell_valid(position))
+        .collect()
+}
+
+/// Number of open cells reachable from `from`
+fn component_size(maze: &Maze, from: GridPosition) -> usize {
+    let mut visited = vec![vec![false; maze.grid[0].len()]; maze.grid.len()];
+    visited[from.x as usize][from.y as usize] = true;
+    let mut stack = vec![from];
+    let mut size = 0;
+    while let Some(position) = stack.pop() {
+        size += 1;
+        for cell in maze.neighbours(&position) {
+            if !visited[cell.x as usize][cell.y as usize] {
+                visited[cell.x as usize][cell.y as usize] = true;
+                stack.push(cell);
+            }
+        }
+    }
+    size
+}
+
+fn options(rows: usize, columns: usize, seed: u64, loop_density: f32) -> GeneratorOptions {
+    GeneratorOptions {
+        rows,
+        columns,
+        seed,
+        loop_density,
+    }
+}
+
+#[test]
+fn perfect_mazes_are_spanning_trees() {
+    for algorithm in MazeGenerateAlgorithm::ALL {
+        for (rows, columns) in [(1, 1), (1, 7), (6, 1), (5, 9), (12, 12)] {
+            for seed in 0..5 {
+                let maze =
+                    MazeGenerator::new(algorithm, &options(rows, columns, seed, 0.0)).generate();
+
+                assert_eq!(maze.grid.len(), 2 * rows + 1);
+                assert_eq!(maze.grid[0].len(), 2 * columns + 1);
+
+                // Every cell plus 1 opened wall per tree edge, all connected
+                let open = open_cells(&maze);
+                assert_eq!(open.len(), 2 * rows * columns - 1, "{:?}", algorithm);
+                assert_eq!(
+                    component_size(&maze, open[0]),
+                    open.len(),
+                    "{:?}",
+                    algorithm
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn same_seed_generates_same_maze() {
+    for algorithm in MazeGenerateAlgorithm::ALL {
+        let generate = |seed| MazeGenerator::new(algorithm, &options(9, 11, seed, 0.2)).generate();
+
+        assert_eq!(generate(7).grid, generate(7).grid, "{:?}", algorithm);
+        // Every carver draws from the seeded generator, so another seed gives another maze
+        assert_ne!(generate(7).grid, generate(8).grid, "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn loop_density_adds_cycles() {
+    for algorithm in MazeGenerateAlgorithm::ALL {
+        let maze = MazeGenerator::new(algorithm, &options(10, 10, 3, 0.3)).generate();
+
+        let open = open_cells(&maze);
+        assert!(open.len() > 2 * 10 * 10 - 1, "{:?}", algorithm);
+        assert_eq!(
+            component_size(&maze, open[0]),
+            open.len(),
+            "{:?}",
+            algorithm
+        );
+    }
+}
+
+#[test]
+fn generated_mazes_parse_back() {
+    for algorithm in MazeGenerateAlgorithm::ALL {
+        let maze = MazeGenerator::new(algorithm, &options(6, 8, 1, 0.1)).generate();
+        let parsed = Maze::new_from_string(&format_maze(&maze)).unwrap();
+
+        assert_eq!(parsed.grid, maze.grid);
+        assert_eq!(parsed.position_start, maze.position_start);
+        assert_eq!(parsed.position_end, maze.position_end);
+    }
+}
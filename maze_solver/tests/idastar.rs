@@ -0,0 +1,144 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+const CONNECTIVITIES: [Connectivity; 4] = [
+    Connectivity::Four,
+    Connectivity::Eight(DiagonalMovement::Always),
+    Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+    Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+];
+
+/// Cache sizes covering no cache, a cache that fills up almost at once and the default one
+const CACHE_SIZES: [Option<usize>; 3] = [Some(0), Some(3), None];
+
+/// Largest side of the random mazes for a cache size, without a cache the searches take exponential time
+fn max_maze_side(cache: Option<usize>) -> usize {
+    match cache {
+        Some(0) => 4,
+        Some(_) => 5,
+        None => 8,
+    }
+}
+
+fn idastar(
+    maze: &Maze,
+    start: GridPosition,
+    end: GridPosition,
+    cache: Option<usize>,
+) -> MazeSolverIDAStar {
+    let solver = MazeSolverIDAStar::new(maze.clone(), start, end);
+    match cache {
+        Some(max_cached_nodes) => solver.with_max_cached_nodes(max_cached_nodes),
+        None => solver,
+    }
+}
+
+fn iddfs(
+    maze: &Maze,
+    start: GridPosition,
+    end: GridPosition,
+    cache: Option<usize>,
+) -> MazeSolverIDDFS {
+    let solver = MazeSolverIDDFS::new(maze.clone(), start, end);
+    match cache {
+        Some(max_cached_nodes) => solver.with_max_cached_nodes(max_cached_nodes),
+        None => solver,
+    }
+}
+
+#[test]
+fn idastar_matches_dijkstra_on_weighted_mazes() {
+    let mut rng = Rng(0x9b05_688c_2b3e_6c1f);
+    for cache in CACHE_SIZES {
+        for _ in 0..100 {
+            let rows = 2 + rng.below(max_maze_side(cache) - 1);
+            let columns = 2 + rng.below(max_maze_side(cache) - 1);
+            let mut maze = random_maze(&mut rng, rows, columns, 9);
+            maze.connectivity = CONNECTIVITIES[rng.below(CONNECTIVITIES.len())];
+            let start = random_open_cell(&mut rng, &maze);
+            let end = random_open_cell(&mut rng, &maze);
+
+            let mut dijkstra = MazeSolverDijkstra::new(maze.clone(), start, end);
+            dijkstra.run();
+            let mut solver = idastar(&maze, start, end, cache);
+            solver.run();
+
+            assert_eq!(solver.outcome(), dijkstra.outcome(), "cache {:?}", cache);
+            if solver.is_complete() {
+                assert_path_valid(&maze, solver.path(), start, end);
+                assert!(
+                    (solver.path_cost() - dijkstra.path_cost()).abs() < 1e-3,
+                    "cache {:?}: {} != {}",
+                    cache,
+                    solver.path_cost(),
+                    dijkstra.path_cost()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn iddfs_matches_bfs_on_mazes() {
+    let mut rng = Rng(0x1f83_d9ab_fb41_bd6b);
+    for cache in CACHE_SIZES {
+        for _ in 0..100 {
+            let rows = 2 + rng.below(max_maze_side(cache) - 1);
+            let columns = 2 + rng.below(max_maze_side(cache) - 1);
+            let mut maze = random_maze(&mut rng, rows, columns, 9);
+            maze.connectivity = CONNECTIVITIES[rng.below(CONNECTIVITIES.len())];
+            let start = random_open_cell(&mut rng, &maze);
+            let end = random_open_cell(&mut rng, &maze);
+
+            let mut bfs = MazeSolverBFS::new(maze.clone(), start, end);
+            bfs.run();
+            let mut solver = iddfs(&maze, start, end, cache);
+            solver.run();
+
+            assert_eq!(solver.outcome(), bfs.outcome(), "cache {:?}", cache);
+            if solver.is_complete() {
+                assert_path_valid(&maze, solver.path(), start, end);
+                assert_eq!(solver.path().len(), bfs.path().len(), "cache {:?}", cache);
+            }
+        }
+    }
+}
+
+#[test]
+fn deepening_searches_start_is_goal() {
+    let maze = Maze::new_from_string("S.\n..").unwrap();
+    let start = maze.position_start.unwrap();
+
+    for cache in CACHE_SIZES {
+        let mut idastar = idastar(&maze, start, start, cache);
+        idastar.run();
+        assert!(idastar.is_complete());
+        assert_eq!(idastar.path(), &vec![start]);
+
+        let mut iddfs = iddfs(&maze, start, start, cache);
+        iddfs.run();
+        assert!(iddfs.is_complete());
+        assert_eq!(iddfs.path(), &vec![start]);
+    }
+}
+
+#[test]
+fn deepening_searches_unreachable_goal() {
+    let maze = Maze::new_from_string("S.#.\n..#G").unwrap();
+    let start = maze.position_start.unwrap();
+    let end = maze.position_end.unwrap();
+
+    for cache in CACHE_SIZES {
+        let mut idastar = idastar(&maze, start, end, cache);
+        idastar.run();
+        assert_eq!(idastar.outcome(), SearchOutcome::Unreachable);
+        assert!(idastar.path().is_empty());
+
+        let mut iddfs = iddfs(&maze, start, end, cache);
+        iddfs.run();
+        assert_eq!(iddfs.outcome(), SearchOutcome::Unreachable);
+        assert!(iddfs.path().is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+#[test]
+fn jps_matches_astar_on_unit_cost_mazes() {
+    let mut rng = Rng(0x6a09_e667_f3bc_c908);
+    let connectivities = [
+        Connectivity::Four,
+        Connectivity::Eight(DiagonalMovement::Always),
+        Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+        Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+    ];
+
+    for _ in 0..300 {
+        let rows = 2 + rng.below(30);
+        let columns = 2 + rng.below(30);
+        let mut maze = random_maze(&mut rng, rows, columns, 0);
+        maze.connectivity = connectivities[rng.below(connectivities.len())];
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut astar = MazeSolverAStar::new(maze.clone(), start, end);
+        astar.run();
+        let mut jps = MazeSolverJPS::new(maze.clone(), start, end);
+        jps.run();
+
+        assert_eq!(jps.outcome(), astar.outcome());
+        if jps.is_complete() {
+            assert_path_valid(&maze, jps.path(), start, end);
+            assert!(
+                (jps.path_cost() - astar.path_cost()).abs() < 1e-3,
+                "{:?}",
+                maze.connectivity
+            );
+        }
+    }
+}
+
+#[test]
+fn jps_expands_fewer_nodes_than_astar_on_open_grids() {
+    let mut rng = Rng(0x3c6e_f372_fe94_f82b);
+    let connectivities = [
+        Connectivity::Four,
+        Connectivity::Eight(DiagonalMovement::Always),
+        Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+        Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+    ];
+
+    for _ in 0..100 {
+        let rows = 2 + rng.below(60);
+        let columns = 2 + rng.below(60);
+        let maze = Maze {
+            grid: vec![vec![0; columns]; rows],
+            position_start: None,
+            position_end: None,
+            connectivity: connectivities[rng.below(connectivities.len())],
+        };
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut astar = MazeSolverAStar::new(maze.clone(), start, end);
+        astar.run();
+        let mut jps = MazeSolverJPS::new(maze.clone(), start, end);
+        jps.run();
+
+        assert!(jps.is_complete());
+        assert!((jps.path_cost() - astar.path_cost()).abs() < 1e-3);
+        assert!(
+            jps.expanded_count() <= astar.expanded_count(),
+            "{:?}: JPS expanded {}, A* {}",
+            maze.connectivity,
+            jps.expanded_count(),
+            astar.expanded_count()
+        );
+    }
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+/// Wall, empty or terrain value for a random cell edit
+fn random_cell_value(rng: &mut Rng) -> u8 {
+    match rng.below(3) {
+        0 => 1,
+        1 => 0,
+        _ => 2 + rng.below(8) as u8,
+    }
+}
+
+fn assert_matches_dijkstra(
+    solver: &dyn MazeSolver,
+    maze: &Maze,
+    start: GridPosition,
+    end: GridPosition,
+) {
+    let mut dijkstra = MazeSolverDijkstra::new(maze.clone(), start, end);
+    dijkstra.run();
+
+    assert_eq!(solver.outcome(), dijkstra.outcome(), "{}", solver.name());
+    if solver.is_complete() {
+        assert_path_valid(maze, solver.path(), start, end);
+        assert!(
+            (solver.path_cost() - dijkstra.path_cost()).abs() < 1e-3,
+            "{}: {} != {}",
+            solver.name(),
+            solver.path_cost(),
+            dijkstra.path_cost()
+        );
+    }
+}
+
+#[test]
+fn repaired_searches_match_dijkstra_after_edits() {
+    let mut rng = Rng(0x510e_527f_ade6_82d1);
+    let connectivities = [
+        Connectivity::Four,
+        Connectivity::Eight(DiagonalMovement::Always),
+        Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+        Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+    ];
+
+    for _ in 0..100 {
+        let rows = 2 + rng.below(20);
+        let columns = 2 + rng.below(20);
+        let mut maze = random_maze(&mut rng, rows, columns, 9);
+        maze.connectivity = connectivities[rng.below(connectivities.len())];
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut lpastar = MazeSolverLPAStar::new(maze.clone(), start, end);
+        let mut dstar_lite = MazeSolverDStarLite::new(maze.clone(), start, end);
+        lpastar.run();
+        dstar_lite.run();
+        assert_matches_dijkstra(&lpastar, &maze, start, end);
+        assert_matches_dijkstra(&dstar_lite, &maze, start, end);
+
+        // D* Lite also follows an agent moving towards the goal
+        let mut agent = start;
+        for _ in 0..5 {
+            for _ in 0..1 + rng.below(4) {
+                let position = GridPosition::new(rng.below(rows) as _, rng.below(columns) as _);
+                if position == start || position == agent || position == end {
+                    continue;
+                }
+                let value = random_cell_value(&mut rng);
+                maze.grid[position.x as usize][position.y as usize] = value;
+                assert!(lpastar.set_cell(&position, value));
+                assert!(dstar_lite.set_cell(&position, value));
+            }
+            lpastar.run();
+            assert_matches_dijkstra(&lpastar, &maze, start, end);
+
+            if rng.below(2) == 0 {
+                agent = random_open_cell(&mut rng, &maze);
+                assert!(dstar_lite.set_start(&agent));
+            }
+            dstar_lite.run();
+            assert_matches_dijkstra(&dstar_lite, &maze, agent, end);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::*;
+use maze_solver::{mapf::random_agents, *};
+
+/// Paths start and end at the agents' cells, only wait or move to a neighbour, and never share a cell or swap
+/// cells at the same time step
+fn assert_paths_valid(solver: &MapfSolver) {
+    let maze = solver.maze();
+    for (index, agent) in solver.agents().iter().enumerate() {
+        let path = &solver.paths()[index];
+        assert_eq!(path.first(), Some(&agent.start));
+        assert_eq!(path.last(), Some(&agent.goal));
+        for step in path.windows(2) {
+            assert!(
+                step[0] == step[1] || maze.neighbours(&step[0]).any(|cell| cell == step[1]),
+                "invalid move {:?} -> {:?}",
+                step[0],
+                step[1]
+            );
+        }
+    }
+
+    for time in 0..=solver.makespan() {
+        for first in 0..solver.agents().len() {
+            for second in first + 1..solver.agents().len() {
+                let (from_first, to_first) = (
+                    solver.position_at(first, time),
+                    solver.position_at(first, time + 1),
+                );
+                let (from_second, to_second) = (
+                    solver.position_at(second, time),
+                    solver.position_at(second, time + 1),
+                );
+                assert_ne!(
+                    from_first, from_second,
+                    "agents {} and {} share a cell at time {}",
+                    first, second, time
+                );
+                assert!(
+                    from_first != to_second || from_second != to_first,
+                    "agents {} and {} swap cells at time {}",
+                    first,
+                    second,
+                    time
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn mapf_paths_are_collision_free() {
+    let mut rng = Rng(0xbb67_ae85_84ca_a73b);
+    let connectivities = [
+        Connectivity::Four,
+        Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+    ];
+
+    for _ in 0..100 {
+        let rows = 3 + rng.below(8);
+        let columns = 3 + rng.below(8);
+        let mut maze = random_maze(&mut rng, rows, columns, 0);
+        maze.connectivity = connectivities[rng.below(connectivities.len())];
+        let Ok(agents) = random_agents(&maze, 2 + rng.below(4), rng.next()) else {
+            continue;
+        };
+
+        let mut cooperative = MapfSolver::new(
+            MapfAlgorithm::CooperativeAStar,
+            maze.clone(),
+            agents.clone(),
+        );
+        cooperative.run();
+        let mut cbs = MapfSolver::new(MapfAlgorithm::ConflictBasedSearch, maze.clone(), agents)
+            .with_max_nodes(200);
+        cbs.run();
+
+        if cooperative.outcome() == SearchOutcome::Found {
+            assert_paths_valid(&cooperative);
+        }
+        if cbs.outcome() == SearchOutcome::Found {
+            assert_paths_valid(&cbs);
+            if cooperative.outcome() == SearchOutcome::Found {
+                assert!(cbs.sum_of_costs() <= cooperative.sum_of_costs());
+            }
+        }
+    }
+}
+
+#[test]
+fn cooperative_astar_failure_is_aborted() {
+    // The first agent rests on the only way past it, but could wait in the side cell until the second one passed
+    let maze = Maze {
+        grid: vec![vec![0, 0, 0], vec![1, 0, 1]],
+        position_start: None,
+        position_end: None,
+        connectivity: Connectivity::Four,
+    };
+    let agents = vec![
+        Agent::new(GridPosition::new(1, 1), GridPosition::new(0, 1)),
+        Agent::new(GridPosition::new(0, 0), GridPosition::new(0, 2)),
+    ];
+
+    let mut cooperative = MapfSolver::new(
+        MapfAlgorithm::CooperativeAStar,
+        maze.clone(),
+        agents.clone(),
+    );
+    cooperative.run();
+    assert_eq!(cooperative.outcome(), SearchOutcome::Aborted);
+    assert!(cooperative.paths().is_empty());
+
+    let mut cbs = MapfSolver::new(MapfAlgorithm::ConflictBasedSearch, maze, agents);
+    cbs.run();
+    assert_eq!(cbs.outcome(), SearchOutcome::Found);
+    assert_paths_valid(&cbs);
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+fn cheapest_cost(maze: &Maze, start: GridPosition, end: GridPosition) -> Option<f32> {
+    let mut dijkstra = MazeSolverDijkstra::new(maze.clone(), start, end);
+    dijkstra.run();
+    dijkstra.is_complete().then(|| dijkstra.path_cost())
+}
+
+/// Cost of the cheapest order, trying every permutation of the goals
+fn brute_force_order_cost(costs: &[Vec<f32>], from: usize, unvisited: &mut Vec<usize>) -> f32 {
+    if unvisited.is_empty() {
+        return 0.0;
+    }
+    let mut cheapest = f32::MAX;
+    for index in 0..unvisited.len() {
+        let goal = unvisited.remove(index);
+        let cost = costs[from][goal + 1] + brute_force_order_cost(costs, goal + 1, unvisited);
+        cheapest = cheapest.min(cost);
+        unvisited.insert(index, goal);
+    }
+    cheapest
+}
+
+#[test]
+fn all_goals_order_matches_brute_force() {
+    let mut rng = Rng(0x3c6e_f372_fe94_f82b);
+    let connectivities = [
+        Connectivity::Four,
+        Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+    ];
+
+    for _ in 0..100 {
+        let rows = 2 + rng.below(15);
+        let columns = 2 + rng.below(15);
+        let mut maze = random_maze(&mut rng, rows, columns, 9);
+        maze.connectivity = connectivities[rng.below(connectivities.len())];
+        let start = random_open_cell(&mut rng, &maze);
+        let goals = (0..1 + rng.below(6))
+            .map(|_| random_open_cell(&mut rng, &maze))
+            .collect::<Vec<_>>();
+
+        let mut solver = MazeSolverAllGoals::new(maze.clone(), start, goals.clone());
+        solver.run();
+
+        // Waypoint 0 is the start and waypoint i > 0 is goal i - 1
+        let waypoints = std::iter::once(start).chain(goals.iter().copied());
+        let costs = waypoints
+            .map(|from| {
+                std::iter::once(start)
+                    .chain(goals.iter().copied())
+                    .map(|to| cheapest_cost(&maze, from, to).unwrap_or(f32::MAX))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        if costs[0].contains(&f32::MAX) {
+            assert_eq!(solver.outcome(), SearchOutcome::Unreachable);
+            continue;
+        }
+
+        assert!(solver.is_complete());
+        let mut order = solver.goal_order().to_vec();
+        order.sort_unstable();
+        assert_eq!(order, (0..goals.len()).collect::<Vec<_>>());
+
+        // The path passes the goals in the reported order
+        let path = solver.path();
+        assert_eq!(path.first(), Some(&start));
+        let mut next = 0;
+        for position in path {
+            while next < goals.len() && goals[solver.goal_order()[next]] == *position {
+                next += 1;
+            }
+        }
+        assert_eq!(next, goals.len());
+
+        let expected = brute_force_order_cost(&costs, 0, &mut (0..goals.len()).collect());
+        assert!(
+            (solver.path_cost() - expected).abs() < 1e-3,
+            "{} != {}",
+            solver.path_cost(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn nearest_goal_matches_brute_force() {
+    let mut rng = Rng(0xa54f_f53a_5f1d_36f1);
+    let options = SolverOptions::default();
+
+    for _ in 0..100 {
+        let rows = 2 + rng.below(20);
+        let columns = 2 + rng.below(20);
+        let mut maze = random_maze(&mut rng, rows, columns, 9);
+        maze.connectivity = Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls);
+        let start = random_open_cell(&mut rng, &maze);
+        let goals = (0..1 + rng.below(5))
+            .map(|_| random_open_cell(&mut rng, &maze))
+            .collect::<Vec<_>>();
+
+        let expected = goals
+            .iter()
+            .filter_map(|&goal| cheapest_cost(&maze, start, goal))
+            .fold(None, |cheapest: Option<f32>, cost| {
+                Some(cheapest.map_or(cost, |cheapest| cheapest.min(cost)))
+            });
+
+        for algorithm in [MazeSolveAlgorithm::Dijkstra, MazeSolveAlgorithm::AStar] {
+            let mut solver = algorithm
+                .new_nearest_goal_solver(maze.clone(), start, goals.clone(), &options)
+                .unwrap();
+            solver.run();
+
+            match expected {
+                Some(expected) => {
+                    assert!(solver.is_complete());
+                    assert!(goals.contains(solver.path().last().unwrap()));
+                    assert!(
+                        (solver.path_cost() - expected).abs() < 1e-3,
+                        "{:?}: {} != {}",
+                        algorithm,
+                        solver.path_cost(),
+                        expected
+                    );
+                }
+                None => assert_eq!(solver.outcome(), SearchOutcome::Unreachable),
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "at least 1 goal")]
+fn all_goals_without_goals_panics() {
+    let maze = Maze::new_from_string("S.\n..").unwrap();
+    MazeSolverAllGoals::new(maze, GridPosition::new(0, 0), Vec::new());
+}
+
+#[test]
+#[should_panic(expected = "at least 1 goal")]
+fn nearest_goal_without_goals_panics() {
+    let maze = Maze::new_from_string("S.\n..").unwrap();
+    MazeSolveAlgorithm::Bfs.new_nearest_goal_solver(
+        maze,
+        GridPosition::new(0, 0),
+        Vec::new(),
+        &SolverOptions::default(),
+    );
+}
@@ -0,0 +1,116 @@
+use maze_solver::{parser::*, GridPosition};
+
+fn parse_error(input: &str) -> ParseError {
+    match parse_maze(input) {
+        Ok(_) => panic!("{:?} should not parse", input),
+        Err(error) => error,
+    }
+}
+
+#[test]
+fn python_list_format() {
+    let maze = parse_maze("[[0, 1, S],\n [G, 4, 0,],]").unwrap();
+
+    assert_eq!(MazeFormat::detect("[[0]]"), Some(MazeFormat::PythonList));
+    assert_eq!(maze.grid, vec![vec![0, 1, 0], vec![0, 4, 0]]);
+    assert_eq!(maze.position_start, Some(GridPosition::new(0, 2)));
+    assert_eq!(maze.position_end, Some(GridPosition::new(1, 0)));
+}
+
+#[test]
+fn digit_grid_format() {
+    let maze = parse_maze("S 1 0\n\n0 12 G\n").unwrap();
+
+    assert_eq!(MazeFormat::detect("0 1\n1 0"), Some(MazeFormat::DigitGrid));
+    assert_eq!(maze.grid, vec![vec![0, 1, 0], vec![0, 12, 0]]);
+    assert_eq!(maze.position_start, Some(GridPosition::new(0, 0)));
+    assert_eq!(maze.position_end, Some(GridPosition::new(1, 2)));
+}
+
+#[test]
+fn ascii_format() {
+    let maze = parse_maze("S.#\n#.G\n").unwrap();
+
+    assert_eq!(MazeFormat::detect("S.#"), Some(MazeFormat::Ascii));
+    assert_eq!(maze.grid, vec![vec![0, 0, 1], vec![1, 0, 0]]);
+    assert_eq!(maze.position_start, Some(GridPosition::new(0, 0)));
+    assert_eq!(maze.position_end, Some(GridPosition::new(1, 2)));
+}
+
+#[test]
+fn ascii_format_ignores_shared_indentation() {
+    let maze = parse_maze("  S.#\n  ..G").unwrap();
+    assert_eq!(maze.grid, vec![vec![0, 0, 1], vec![0, 0, 0]]);
+    assert_eq!(maze.position_end, Some(GridPosition::new(1, 2)));
+
+    // Columns are still reported in the original text
+    let error = parse_error("  S.#\n  .xG");
+    assert_eq!((error.line, error.column), (2, 4));
+}
+
+#[test]
+fn unrecognised_format() {
+    let error = parse_error("\n  hello");
+    assert_eq!((error.line, error.column), (2, 3));
+    assert_eq!(parse_error("").message, "unrecognised maze format");
+}
+
+#[test]
+fn ragged_rows_are_rejected() {
+    let error = parse_error("[[0, 1],\n [0]]");
+    assert_eq!((error.line, error.column), (2, 2));
+    assert!(error.message.contains("row has 1 cells, expected 2"));
+
+    let error = parse_error("0 1\n0 1 0");
+    assert_eq!((error.line, error.column), (2, 1));
+
+    let error = parse_error("S.#\n\n..");
+    assert_eq!((error.line, error.column), (3, 1));
+}
+
+#[test]
+fn bad_cell_values_are_rejected() {
+    let error = parse_error("[[0, x]]");
+    assert_eq!((error.line, error.column), (1, 6));
+    assert_eq!(error.message, "invalid cell value 'x'");
+
+    let error = parse_error("0 1\n1 256");
+    assert_eq!((error.line, error.column), (2, 3));
+    assert_eq!(error.message, "cell value 256 is out of range (0-255)");
+
+    let error = parse_error("S.#\n.*G");
+    assert_eq!((error.line, error.column), (2, 2));
+
+    let error = parse_error("[[0, 1]");
+    assert_eq!((error.line, error.column), (1, 8));
+    assert_eq!(error.message, "expected ']', found end of input");
+}
+
+#[test]
+fn duplicate_markers_are_rejected() {
+    let error = parse_error("S.S\n..G");
+    assert_eq!((error.line, error.column), (1, 3));
+    assert_eq!(error.message, "maze contains more than one start marker");
+
+    let error = parse_error("[[S, G],\n [0, G]]");
+    assert_eq!((error.line, error.column), (2, 6));
+    assert_eq!(error.message, "maze contains more than one goal marker");
+}
+
+#[test]
+fn formatted_maze_parses_back() {
+    let maze = parse_maze("S.#\n#.G").unwrap();
+    let parsed = parse_maze(&format_maze(&maze)).unwrap();
+
+    assert_eq!(parsed.grid, maze.grid);
+    assert_eq!(parsed.position_start, maze.position_start);
+    assert_eq!(parsed.position_end, maze.position_end);
+}
+
+#[test]
+fn error_display_includes_location() {
+    assert_eq!(
+        parse_error("0 1\n1 x").to_string(),
+        "line 2, column 3: invalid cell value 'x'"
+    );
+}
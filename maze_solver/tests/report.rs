@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use maze_solver::{report::*, *};
+
+fn solver(maze: &str) -> MazeSolverBFS {
+    let maze = Maze::new_from_string(maze).unwrap();
+    let start = maze.position_start.unwrap();
+    let end = maze.position_end.unwrap();
+    MazeSolverBFS::new(maze, start, end)
+}
+
+/// CSV row of a single report, keyed by the header columns
+fn csv_row(report: &SolverReport) -> HashMap<String, String> {
+    let csv = format_reports(std::slice::from_ref(report), ReportFormat::Csv);
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    lines[0]
+        .split(',')
+        .zip(lines[1].split(','))
+        .map(|(column, value)| (column.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn outcome_names() {
+    let names = [
+        (SearchOutcome::InProgress, "in_progress"),
+        (SearchOutcome::Found, "found"),
+        (SearchOutcome::Unreachable, "unreachable"),
+        (SearchOutcome::Aborted, "aborted"),
+    ];
+    for (outcome, name) in names {
+        assert_eq!(outcome.to_string(), name);
+        assert_eq!(
+            serde_json::to_string(&outcome).unwrap(),
+            format!("\"{}\"", name)
+        );
+    }
+}
+
+#[test]
+fn found_report() {
+    let report = run_with_report(&mut solver("S.#\n#.G"), None);
+
+    assert_eq!(report.outcome, SearchOutcome::Found);
+    assert_eq!(report.path_cost, Some(3.0));
+    assert_eq!(report.path.len(), 4);
+
+    let row = csv_row(&report);
+    assert_eq!(row["algorithm"], "BFS");
+    assert_eq!(row["outcome"], "found");
+    assert_eq!(row["path_cost"], "3");
+    // The path is a single column of space separated x;y pairs
+    assert_eq!(row["path"], "0;0 0;1 1;1 1;2");
+
+    let json = format_reports(&[report], ReportFormat::Json);
+    assert!(json.contains("\"outcome\": \"found\""));
+}
+
+#[test]
+fn unreachable_report() {
+    let report = run_with_report(&mut solver("S#G"), None);
+
+    assert_eq!(report.outcome, SearchOutcome::Unreachable);
+    assert_eq!(report.path_cost, None);
+    assert!(report.path.is_empty());
+
+    let row = csv_row(&report);
+    assert_eq!(row["outcome"], "unreachable");
+    assert_eq!(row["path_cost"], "");
+    assert_eq!(row["path"], "");
+}
+
+#[test]
+fn max_expansions_aborts() {
+    let mut solver = solver("S.........\n..........\n.........G");
+    let report = run_with_report(&mut solver, Some(5));
+
+    assert_eq!(report.outcome, SearchOutcome::Aborted);
+    assert_eq!(report.path_cost, None);
+    assert!(report.expanded_nodes > 5);
+    assert_eq!(csv_row(&report)["outcome"], "aborted");
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+const CONNECTIVITIES: [Connectivity; 4] = [
+    Connectivity::Four,
+    Connectivity::Eight(DiagonalMovement::Always),
+    Connectivity::Eight(DiagonalMovement::IfAtMostOneWall),
+    Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls),
+];
+
+#[test]
+fn weighted_astar_stays_within_its_bound() {
+    let mut rng = Rng(0x7137_4491_b5c0_fbcf);
+    for _ in 0..300 {
+        let rows = 2 + rng.below(30);
+        let columns = 2 + rng.below(30);
+        let mut maze = random_maze(&mut rng, rows, columns, 9);
+        maze.connectivity = CONNECTIVITIES[rng.below(CONNECTIVITIES.len())];
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+        let weight = [1.5, 2.0, 3.0][rng.below(3)];
+
+        let mut dijkstra = MazeSolverDijkstra::new(maze.clone(), start, end);
+        dijkstra.run();
+        let mut weighted = MazeSolverAStar::new(maze.clone(), start, end).with_weight(weight);
+        weighted.run();
+
+        assert_eq!(weighted.outcome(), dijkstra.outcome());
+        if !weighted.is_complete() || dijkstra.path_cost() == 0.0 {
+            continue;
+        }
+        assert_path_valid(&maze, weighted.path(), start, end);
+
+        let ratio = weighted.path_cost() / dijkstra.path_cost();
+        let bound = weighted.suboptimality_bound().unwrap();
+        assert!(ratio <= weight + 1e-3, "{} > {}", ratio, weight);
+        assert!(bound <= weight + 1e-3, "{} > {}", bound, weight);
+        assert!(ratio <= bound + 1e-3, "{} > {}", ratio, bound);
+    }
+}
+
+#[test]
+fn beam_search_reports_pruned_goal_as_aborted() {
+    // The narrowest beam follows the heuristic into the dead end and drops the way down
+    let maze = Maze::new_from_string("#####\nS..#G\n.###.\n.....").unwrap();
+    let start = maze.position_start.unwrap();
+    let end = maze.position_end.unwrap();
+
+    let mut beam = MazeSolverBeam::new(maze.clone(), start, end, 1);
+    beam.run();
+    assert_eq!(beam.outcome(), SearchOutcome::Aborted);
+    assert!(beam.path().is_empty());
+
+    let mut wide_beam = MazeSolverBeam::new(maze.clone(), start, end, 2);
+    wide_beam.run();
+    assert!(wide_beam.is_complete());
+    assert_path_valid(&maze, wide_beam.path(), start, end);
+}
+
+#[test]
+fn beam_search_reports_unreachable_goal() {
+    let maze = Maze::new_from_string("S.#G\n..#.").unwrap();
+    let start = maze.position_start.unwrap();
+    let end = maze.position_end.unwrap();
+
+    let mut beam = MazeSolverBeam::new(maze, start, end, 1);
+    beam.run();
+    assert_eq!(beam.outcome(), SearchOutcome::Unreachable);
+}
+
+#[test]
+fn greedy_finds_a_valid_path_whenever_one_exists() {
+    let mut rng = Rng(0xe9b5_dba5_8189_dbbc);
+    for _ in 0..300 {
+        let rows = 2 + rng.below(30);
+        let columns = 2 + rng.below(30);
+        let mut maze = random_maze(&mut rng, rows, columns, 9);
+        maze.connectivity = CONNECTIVITIES[rng.below(CONNECTIVITIES.len())];
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut bfs = MazeSolverBFS::new(maze.clone(), start, end);
+        bfs.run();
+        let mut greedy = MazeSolverGreedyBestFirst::new(maze.clone(), start, end);
+        greedy.run();
+
+        assert_eq!(greedy.outcome(), bfs.outcome());
+        if greedy.is_complete() {
+            assert_path_valid(&maze, greedy.path(), start, end);
+        }
+    }
+}
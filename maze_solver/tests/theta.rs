@@ -0,0 +1,83 @@
+mod common;
+
+use common::*;
+use maze_solver::*;
+
+fn assert_waypoints_visible(
+    maze: &Maze,
+    path: &[GridPosition],
+    start: GridPosition,
+    end: GridPosition,
+) {
+    assert_eq!(path.first(), Some(&start));
+    assert_eq!(path.last(), Some(&end));
+    for segment in path.windows(2) {
+        assert!(
+            maze.has_line_of_sight(&segment[0], &segment[1]),
+            "no line of sight {:?} -> {:?}",
+            segment[0],
+            segment[1]
+        );
+    }
+}
+
+#[test]
+fn any_angle_paths_are_no_longer_than_grid_paths() {
+    let mut rng = Rng(0x923f_82a4_ab1c_5ed5);
+    let (mut theta_total, mut lazy_theta_total) = (0.0, 0.0);
+    for _ in 0..300 {
+        let rows = 2 + rng.below(30);
+        let columns = 2 + rng.below(30);
+        let mut maze = random_maze(&mut rng, rows, columns, 0);
+        maze.connectivity = Connectivity::Eight(DiagonalMovement::OnlyWhenNoWalls);
+        let start = random_open_cell(&mut rng, &maze);
+        let end = random_open_cell(&mut rng, &maze);
+
+        let mut astar = MazeSolverAStar::new(maze.clone(), start, end);
+        astar.run();
+        let mut theta = MazeSolverThetaStar::new(maze.clone(), start, end);
+        theta.run();
+        let mut lazy_theta = MazeSolverThetaStar::new(maze.clone(), start, end).lazy();
+        lazy_theta.run();
+
+        assert_eq!(theta.outcome(), astar.outcome());
+        assert_eq!(lazy_theta.outcome(), astar.outcome());
+        if !astar.is_complete() {
+            continue;
+        }
+
+        assert_waypoints_visible(&maze, theta.path(), start, end);
+        assert_waypoints_visible(&maze, lazy_theta.path(), start, end);
+        assert!(theta.path_cost() <= astar.path_cost() + 1e-3);
+        assert!(lazy_theta.path_cost() <= astar.path_cost() + 1e-3);
+        theta_total += theta.path_cost();
+        lazy_theta_total += lazy_theta.path_cost();
+    }
+
+    // Neither is optimal and either can win on a single maze, but Theta* checking line of sight eagerly should
+    // not lose overall
+    assert!(
+        theta_total <= lazy_theta_total,
+        "Theta* {} > Lazy Theta* {}",
+        theta_total,
+        lazy_theta_total
+    );
+}
+
+#[test]
+fn any_angle_searches_report_unreachable_goal() {
+    let maze = Maze::new_from_string("S.#.\n..#G").unwrap();
+    let start = maze.position_start.unwrap();
+    let end = maze.position_end.unwrap();
+
+    for lazy in [false, true] {
+        let mut solver = MazeSolverThetaStar::new(maze.clone(), start, end);
+        if lazy {
+            solver = solver.lazy();
+        }
+        solver.run();
+
+        assert_eq!(solver.outcome(), SearchOutcome::Unreachable);
+        assert!(solver.path().is_empty());
+    }
+}